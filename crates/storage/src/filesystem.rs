@@ -1,12 +1,15 @@
 //   filesystem:///var/iridium-storage/
 
-use std::fs::File;
-use std::io::{BufWriter, Write};
 use std::path::PathBuf;
 
 use chrono::Utc;
+use tokio::io::AsyncWriteExt;
 
-use directip::Message;
+use directip::sequence::MomsnTracker;
+use directip::{Imei, Message};
+
+/// File with the last MOMSN of each modem, one `IMEI MOMSN` per line
+const MOMSN_FILE: &str = "momsn.txt";
 
 #[derive(Debug)]
 pub struct FileSystemStorage {
@@ -22,50 +25,103 @@ impl FileSystemStorage {
         Ok(FileSystemStorage { root: path })
     }
 
-    pub(super) async fn save(&self, msg: Message) {
+    pub(super) async fn save(&self, msg: Message) -> Result<(), Box<dyn std::error::Error>> {
         let mut path = self.root.clone();
 
         // Data directory
         path.push("data");
-        if !path.exists() {
+        if !tokio::fs::try_exists(&path).await? {
             tracing::warn!("Creating missing data directory: {:?}", path);
-            std::fs::create_dir(&path).unwrap();
+            tokio::fs::create_dir_all(&path).await?;
         }
 
         // A directory for each modem
         let imei = match msg.imei() {
-            Some(i) => i
-                .into_iter()
-                .map(|d| format!("{:02x?}", d))
-                .collect::<String>(),
+            Some(i) => i.to_string(),
             None => "Unknown".to_string(),
         };
         path.push(imei);
-        if !path.exists() {
+        if !tokio::fs::try_exists(&path).await? {
             tracing::info!("Creating directory for new platform: {:?}", path);
-            std::fs::create_dir(&path).unwrap();
+            tokio::fs::create_dir_all(&path).await?;
         }
 
         // One modem can accumulate a lot of messages. The issue here is not
         // size, but the number of items for some file systems.
-        path.push(&Utc::now().format("%Y").to_string());
-        if !path.exists() {
+        let now = Utc::now();
+        path.push(now.format("%Y").to_string());
+        if !tokio::fs::try_exists(&path).await? {
             tracing::info!("New annual directory: {:?}", path);
-            std::fs::create_dir(&path).unwrap();
+            tokio::fs::create_dir_all(&path).await?;
         }
 
-        let mut filename = String::new();
-        // Add IMEI?
-        filename.push_str(&Utc::now().format("%Y%m%d%H%M%S%s").to_string());
-        //filename.push_str(&format!("_{}", &self.current_id));
-        filename.push_str(".isbd");
-        tracing::debug!("Message filename: {:?}", filename);
+        // Several messages can arrive within a second, even with the same
+        // MOMSN if retransmitted, thus a counter when a name is taken.
+        let mut stem = now.format("%Y%m%d%H%M%S").to_string();
+        if let Message::MO(mo) = &msg {
+            if let Some(header) = mo.header() {
+                stem.push_str(&format!("_{}", header.momsn()));
+            }
+        }
+        let contents = msg.to_vec();
+        let mut counter = 0;
+        loop {
+            let filename = match counter {
+                0 => format!("{}.isbd", stem),
+                n => format!("{}_{}.isbd", stem, n),
+            };
+            let file = tokio::fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(path.join(&filename))
+                .await;
+            match file {
+                Ok(mut file) => {
+                    tracing::info!("Saving message as: {:?}", path.join(filename));
+                    file.write_all(&contents).await?;
+                    file.sync_all().await?;
+                    return Ok(());
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => counter += 1,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
 
-        path.push(filename);
+    pub(super) async fn load_momsn(&self) -> Result<MomsnTracker, Box<dyn std::error::Error>> {
+        let path = self.root.join(MOMSN_FILE);
+        if !tokio::fs::try_exists(&path).await? {
+            return Ok(MomsnTracker::new());
+        }
+        let content = tokio::fs::read_to_string(&path).await?;
+        content
+            .lines()
+            .map(|line| {
+                let (imei, momsn) = line
+                    .split_once(' ')
+                    .ok_or_else(|| format!("Invalid line in {:?}: {}", path, line))?;
+                let imei = Imei::from_display(imei).map_err(|e| format!("{} in {:?}", e, path))?;
+                Ok((imei, momsn.parse::<u16>()?))
+            })
+            .collect()
+    }
 
-        tracing::info!("Saving message as: {:?}", path);
-        let mut file = BufWriter::new(File::create(path).unwrap());
-        file.write(&msg.to_vec()).unwrap();
+    pub(super) async fn save_momsn(
+        &self,
+        tracker: &MomsnTracker,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Write aside and rename, so that a crash never leaves it truncated
+        let path = self.root.join(MOMSN_FILE);
+        let tmp = path.with_extension("tmp");
+        let mut content = String::new();
+        for (imei, momsn) in tracker.iter() {
+            content.push_str(&format!("{} {}\n", imei, momsn));
+        }
+        let mut file = tokio::fs::File::create(&tmp).await?;
+        file.write_all(content.as_bytes()).await?;
+        file.sync_all().await?;
+        tokio::fs::rename(tmp, path).await?;
+        Ok(())
     }
 
     /*
@@ -111,6 +167,58 @@ mod test_filesystem {
     async fn filesystem() {
         let tmp_dir = tempfile::TempDir::new().unwrap();
         let storage = FileSystemStorage::connect(tmp_dir.into_path()).unwrap();
-        storage.save(sample()).await;
+        storage.save(sample()).await.unwrap();
+    }
+
+    #[tokio::test]
+    // The same message saved twice, within a second, is kept twice
+    async fn save_twice() {
+        let tmp_dir = tempfile::TempDir::new().unwrap();
+        let storage = FileSystemStorage::connect(tmp_dir.path().to_path_buf()).unwrap();
+        storage.save(sample()).await.unwrap();
+        storage.save(sample()).await.unwrap();
+
+        let saved: Vec<_> = walk(tmp_dir.path().join("data"));
+        assert_eq!(saved.len(), 2);
+        for path in saved {
+            assert_eq!(std::fs::read(path).unwrap(), sample().to_vec());
+        }
+    }
+
+    /// All files under a directory
+    fn walk(path: PathBuf) -> Vec<PathBuf> {
+        if path.is_dir() {
+            std::fs::read_dir(path)
+                .unwrap()
+                .flat_map(|entry| walk(entry.unwrap().path()))
+                .collect()
+        } else {
+            vec![path]
+        }
+    }
+
+    #[tokio::test]
+    async fn momsn() {
+        let tmp_dir = tempfile::TempDir::new().unwrap();
+        let storage = FileSystemStorage::connect(tmp_dir.path().to_path_buf()).unwrap();
+
+        let mut tracker = MomsnTracker::new();
+        tracker.track_momsn("300234010753376".parse().unwrap(), 42);
+        // IMEI that is not numeric, shown in hexadecimal
+        tracker.track_momsn([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14].into(), 7);
+        storage.save_momsn(&tracker).await.unwrap();
+
+        let content = std::fs::read_to_string(tmp_dir.path().join(MOMSN_FILE)).unwrap();
+        assert!(content.contains("300234010753376 42\n"));
+        assert!(content.contains("000102030405060708090a0b0c0d0e 7\n"));
+        assert_eq!(storage.load_momsn().await.unwrap(), tracker);
+    }
+
+    #[tokio::test]
+    async fn momsn_invalid() {
+        let tmp_dir = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp_dir.path().join(MOMSN_FILE), "300234010753376\n").unwrap();
+        let storage = FileSystemStorage::connect(tmp_dir.path().to_path_buf()).unwrap();
+        assert!(storage.load_momsn().await.is_err());
     }
 }
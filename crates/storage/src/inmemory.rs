@@ -1,11 +1,12 @@
 use std::sync::RwLock;
 
-use crate::Message;
+use crate::{Message, MomsnTracker};
 
 #[derive(Debug)]
 pub struct VolatileStorage {
     // current_id: usize,
     data: RwLock<Vec<Message>>,
+    momsn: RwLock<MomsnTracker>,
 }
 
 impl super::Storage for VolatileStorage {}
@@ -14,14 +15,27 @@ impl VolatileStorage {
     pub(super) fn connect() -> Result<VolatileStorage, Box<dyn std::error::Error>> {
         Ok(VolatileStorage {
             data: RwLock::new(vec![]),
+            momsn: RwLock::new(MomsnTracker::new()),
         })
     }
 
-    pub(super) async fn save(&self, msg: Message) {
+    pub(super) async fn save(&self, msg: Message) -> Result<(), Box<dyn std::error::Error>> {
         self.data
             .write()
             .expect("Failed to acquire write lock.")
             .push(msg);
+        Ok(())
+    }
+
+    pub(super) async fn load_momsn(&self) -> MomsnTracker {
+        self.momsn
+            .read()
+            .expect("Failed to acquire read lock.")
+            .clone()
+    }
+
+    pub(super) async fn save_momsn(&self, tracker: &MomsnTracker) {
+        *self.momsn.write().expect("Failed to acquire write lock.") = tracker.clone();
     }
 }
 
@@ -33,7 +47,7 @@ mod test_volatile {
     #[tokio::test]
     async fn volatile() {
         let storage = VolatileStorage::connect().unwrap();
-        storage.save(sample()).await;
+        storage.save(sample()).await.unwrap();
 
         assert_eq!(storage.data.read().unwrap().len(), 1);
     }
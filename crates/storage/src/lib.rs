@@ -1,6 +1,7 @@
 mod filesystem;
 mod inmemory;
 
+use directip::sequence::MomsnTracker;
 use directip::Message;
 use filesystem::FileSystemStorage;
 use inmemory::VolatileStorage;
@@ -9,13 +10,14 @@ use inmemory::VolatileStorage;
 #[cfg(feature = "sqlite")]
 mod sqlite;
 
+#[allow(dead_code)]
 trait Storage {
     //fn connect(cfg: String) -> Self;
     // fn save(&self, msg: Message);
 }
 
 #[derive(Debug)]
-enum Database {
+pub enum Database {
     M(VolatileStorage),
     F(FileSystemStorage),
     #[cfg(feature = "sqlite")]
@@ -24,22 +26,22 @@ enum Database {
 
 impl Database {
     pub async fn open(cfg: &str) -> Result<Self, Box<dyn std::error::Error>> {
-        if (&cfg.len() >= &(11 as usize)) && (cfg[..11] == "volatile://".to_string()) {
+        if cfg.starts_with("volatile://") {
             Ok(Database::M(VolatileStorage::connect()?))
-        } else if (&cfg.len() >= &(13 as usize)) && (cfg[..13] == "filesystem://".to_string()) {
+        } else if let Some(path) = cfg.strip_prefix("filesystem://") {
             Ok(Database::F(FileSystemStorage::connect(
-                std::path::PathBuf::from(cfg[13..].to_string()),
+                std::path::PathBuf::from(path),
             )?))
-        } else if cfg[..9] == "sqlite://".to_string() {
+        } else if cfg.starts_with("sqlite://") {
             #[cfg(feature = "sqlite")]
             {
-                let db = crate::sqlite::SQLiteStorage::connect().await;
+                let db = crate::sqlite::SQLiteStorage::connect(cfg).await?;
                 Ok(Database::L(db))
             }
             #[cfg(not(feature = "sqlite"))]
-            unimplemented!("Missing sqlite feature")
+            Err("sqlite:// storage requires the sqlite feature".into())
         } else {
-            unimplemented!("Unknown storage")
+            Err(format!("Unknown storage: {}", cfg).into())
         }
         /*
         Database::F(FileSystemStorage::initiatedb(std::path::PathBuf::from(
@@ -48,7 +50,7 @@ impl Database {
         */
     }
 
-    pub async fn save(&self, msg: Message) {
+    pub async fn save(&self, msg: Message) -> Result<(), Box<dyn std::error::Error>> {
         match self {
             Database::M(s) => s.save(msg).await,
             Database::F(s) => s.save(msg).await,
@@ -56,17 +58,44 @@ impl Database {
             Database::L(s) => s.save(msg).await,
         }
     }
+
+    /// Restore the last MOMSN of each modem, empty if never saved
+    pub async fn load_momsn(&self) -> Result<MomsnTracker, Box<dyn std::error::Error>> {
+        match self {
+            Database::M(s) => Ok(s.load_momsn().await),
+            Database::F(s) => s.load_momsn().await,
+            #[cfg(feature = "sqlite")]
+            Database::L(s) => s.load_momsn().await,
+        }
+    }
+
+    /// Persist the last MOMSN of each modem
+    pub async fn save_momsn(
+        &self,
+        tracker: &MomsnTracker,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match self {
+            Database::M(s) => {
+                s.save_momsn(tracker).await;
+                Ok(())
+            }
+            Database::F(s) => s.save_momsn(tracker).await,
+            #[cfg(feature = "sqlite")]
+            Database::L(s) => s.save_momsn(tracker).await,
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::Database;
     use directip::sample;
+    use directip::sequence::MomsnTracker;
 
     #[tokio::test]
     async fn volatile() {
         let db = Database::open("volatile://").await.unwrap();
-        db.save(sample()).await;
+        db.save(sample()).await.unwrap();
     }
 
     #[tokio::test]
@@ -75,13 +104,35 @@ mod tests {
         let mut cfg = String::from("filesystem://");
         cfg.push_str(tmp_dir.path().to_str().unwrap());
         let db = Database::open(&cfg).await.unwrap();
-        db.save(sample()).await;
+        db.save(sample()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn momsn_roundtrip() {
+        let tmp_dir = tempfile::TempDir::new().unwrap();
+        let cfg = format!("filesystem://{}", tmp_dir.path().to_str().unwrap());
+        for cfg in ["volatile://", &cfg] {
+            let db = Database::open(cfg).await.unwrap();
+            assert_eq!(db.load_momsn().await.unwrap(), MomsnTracker::new());
+
+            let mut tracker = MomsnTracker::new();
+            tracker.track_momsn("300234010753376".parse().unwrap(), 42);
+            db.save_momsn(&tracker).await.unwrap();
+            assert_eq!(db.load_momsn().await.unwrap(), tracker);
+        }
+    }
+
+    #[tokio::test]
+    async fn open_unknown() {
+        assert!(Database::open("mssql://").await.is_err());
+        #[cfg(not(feature = "sqlite"))]
+        assert!(Database::open("sqlite://:memory:").await.is_err());
     }
 
     #[cfg(feature = "sqlite")]
     #[tokio::test]
     async fn open_sqlite() {
-        let db = Database::open("sqlite://").await.unwrap();
-        db.save(sample()).await;
+        let db = Database::open("sqlite://:memory:").await.unwrap();
+        db.save(sample()).await.unwrap();
     }
 }
@@ -1,9 +1,9 @@
-use sqlx;
+use std::str::FromStr;
 
-#[cfg(test)]
-use tokio;
+use sqlx::sqlite::SqliteConnectOptions;
 
-use directip::Message;
+use directip::sequence::MomsnTracker;
+use directip::{Imei, Message};
 
 #[derive(Debug)]
 pub struct SQLiteStorage {
@@ -13,26 +13,64 @@ pub struct SQLiteStorage {
 impl super::Storage for SQLiteStorage {}
 
 impl SQLiteStorage {
-    pub async fn connect() -> Result<SQLiteStorage, Box<dyn std::error::Error>> {
-        // let pool = SqlitePool::connect("mssql://").await?;
-        let pool = sqlx::SqlitePool::connect("sqlite://:memory:").await?;
+    /// Connect to the database at the URL, such as `sqlite:///var/iridium.db`
+    ///
+    /// The database is created if missing. Use `sqlite://:memory:` for an
+    /// in-memory database.
+    pub async fn connect(url: &str) -> Result<SQLiteStorage, Box<dyn std::error::Error>> {
+        let options = SqliteConnectOptions::from_str(url)?.create_if_missing(true);
+        let pool = sqlx::SqlitePool::connect_with(options).await?;
 
         sqlx::query(
             "CREATE TABLE IF NOT EXISTS inbox (
                 payload BLOB);",
         )
         .execute(&pool)
-        .await
-        .unwrap();
+        .await?;
+
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS momsn (
+                imei BLOB PRIMARY KEY,
+                momsn INTEGER NOT NULL);",
+        )
+        .execute(&pool)
+        .await?;
 
         Ok(SQLiteStorage { pool })
     }
 
-    pub(super) async fn save(&self, msg: Message) {
-        let _ = sqlx::query("INSERT INTO inbox (payload) VALUES ($1)")
+    pub(super) async fn save(&self, msg: Message) -> Result<(), Box<dyn std::error::Error>> {
+        sqlx::query("INSERT INTO inbox (payload) VALUES ($1)")
             .bind(msg.to_vec())
             .execute(&self.pool)
-            .await;
+            .await?;
+        Ok(())
+    }
+
+    pub(super) async fn load_momsn(&self) -> Result<MomsnTracker, Box<dyn std::error::Error>> {
+        let rows: Vec<(Vec<u8>, i64)> = sqlx::query_as("SELECT imei, momsn FROM momsn")
+            .fetch_all(&self.pool)
+            .await?;
+        rows.into_iter()
+            .map(|(imei, momsn)| Ok((Imei::try_from(imei.as_slice())?, u16::try_from(momsn)?)))
+            .collect()
+    }
+
+    pub(super) async fn save_momsn(
+        &self,
+        tracker: &MomsnTracker,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("DELETE FROM momsn").execute(&mut *tx).await?;
+        for (imei, momsn) in tracker.iter() {
+            sqlx::query("INSERT INTO momsn (imei, momsn) VALUES ($1, $2)")
+                .bind(imei.as_bytes().to_vec())
+                .bind(i64::from(momsn))
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
     }
 }
 
@@ -43,7 +81,42 @@ mod test_sqlite {
 
     #[tokio::test]
     async fn sqlite_inmemory() {
-        let db = SQLiteStorage::connect().await.unwrap();
-        db.save(sample());
+        let db = SQLiteStorage::connect("sqlite://:memory:").await.unwrap();
+        db.save(sample()).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn momsn() {
+        let db = SQLiteStorage::connect("sqlite://:memory:").await.unwrap();
+        assert_eq!(db.load_momsn().await.unwrap(), MomsnTracker::new());
+
+        let mut tracker = MomsnTracker::new();
+        tracker.track_momsn("300234010753376".parse().unwrap(), 42);
+        db.save_momsn(&tracker).await.unwrap();
+        assert_eq!(db.load_momsn().await.unwrap(), tracker);
+    }
+
+    #[tokio::test]
+    // Saved in the file given, which persists after reconnecting
+    async fn sqlite_file() {
+        let tmp_dir = tempfile::TempDir::new().unwrap();
+        let path = tmp_dir.path().join("iridium.db");
+        let url = format!("sqlite://{}", path.to_str().unwrap());
+
+        let mut tracker = MomsnTracker::new();
+        tracker.track_momsn("300234010753376".parse().unwrap(), 42);
+        let db = SQLiteStorage::connect(&url).await.unwrap();
+        db.save(sample()).await.unwrap();
+        db.save_momsn(&tracker).await.unwrap();
+        db.pool.close().await;
+        assert!(path.is_file());
+
+        let db = SQLiteStorage::connect(&url).await.unwrap();
+        assert_eq!(db.load_momsn().await.unwrap(), tracker);
+        let (count,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM inbox")
+            .fetch_one(&db.pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
     }
 }
@@ -16,6 +16,7 @@ extern crate log;
 
 use clap::{Arg, ArgAction, Command};
 use directip::mt::MTMessage;
+use directip::Imei;
 // use log::LevelFilter;
 use std::fs::File;
 use std::io::{stdin, BufReader, Read, Write};
@@ -131,9 +132,9 @@ fn main() -> anyhow::Result<()> {
     debug!("Composing MT-Message");
     let msg = MTMessage::builder()
         .client_msg_id(msg_id)
-        .imei(imei.as_bytes().try_into().unwrap())
+        .imei(imei.parse::<Imei>()?)
         .payload(payload)
-        .build();
+        .build()?;
 
     /*
         disposition_flags: DispositionFlags::decode(0x0000),
@@ -172,9 +173,10 @@ mod test {
     fn tmp() {
         let msg = MTMessage::builder()
             .client_msg_id(999)
-            .imei([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14])
+            .imei("300234010753376".parse().unwrap())
             .payload("Hello".into())
             .build()
+            .unwrap()
             .to_vec();
 
         dbg!(msg);
@@ -10,7 +10,7 @@ fn missing_msg_id() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("directip-client")?;
 
     cmd.args(["--server=127.0.0.1:10800"])
-        .args(["--imei=012345678901234"])
+        .args(["--imei=012345678901237"])
         .arg("42")
         .assert()
         .failure()
@@ -25,7 +25,7 @@ fn missing_msg_id() -> Result<(), Box<dyn std::error::Error>> {
 fn missing_server() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("directip-client")?;
 
-    cmd.args(["--imei=012345678901234"])
+    cmd.args(["--imei=012345678901237"])
         .args(["--msg-id=987"])
         .arg("42")
         .assert()
@@ -61,7 +61,7 @@ fn ascii_inline() -> Result<(), Box<dyn std::error::Error>> {
 
     cmd.args(["--msg-id=987"])
         .args(["--server=127.0.0.1:10800"])
-        .args(["--imei=012345678901234"])
+        .args(["--imei=012345678901237"])
         .args(["--dry-run"])
         .arg("42")
         .assert()
@@ -84,7 +84,7 @@ fn ascii_fromfile() -> Result<(), Box<dyn std::error::Error>> {
 
     cmd.args(["--msg-id=987"])
         .args(["--server=127.0.0.1:10800"])
-        .args(["--imei=012345678901234"])
+        .args(["--imei=012345678901237"])
         .args(["--from-file"])
         .args(["--dry-run"])
         .arg(file.path())
@@ -111,7 +111,7 @@ fn binary_fromfile() -> Result<(), Box<dyn std::error::Error>> {
     let mut cmd = Command::cargo_bin("directip-client")?;
     cmd.args(["--msg-id=987"])
         .args(["--server=127.0.0.1:10800"])
-        .args(["--imei=012345678901234"])
+        .args(["--imei=012345678901237"])
         .args(["--encoding=binary"])
         .args(["--from-file"])
         .args(["--dry-run"])
@@ -133,7 +133,7 @@ fn ascii_stdin() -> Result<(), Box<dyn std::error::Error>> {
 
     cmd.args(["--msg-id=987"])
         .args(["--server=127.0.0.1:10800"])
-        .args(["--imei=012345678901234"])
+        .args(["--imei=012345678901237"])
         .args(["--dry-run"])
         .write_stdin("42")
         .assert()
@@ -157,7 +157,7 @@ fn binary_stdin() -> Result<(), Box<dyn std::error::Error>> {
 
     cmd.args(["--msg-id=987"])
         .args(["--server=127.0.0.1:10800"])
-        .args(["--imei=012345678901234"])
+        .args(["--imei=012345678901237"])
         .args(["--dry-run"])
         .write_stdin(payload)
         .assert()
@@ -167,3 +167,19 @@ fn binary_stdin() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+// IMEI with an invalid check digit
+fn invalid_imei() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("directip-client")?;
+
+    cmd.args(["--msg-id=987"])
+        .args(["--server=127.0.0.1:10800"])
+        .args(["--imei=012345678901234"])
+        .args(["--dry-run"])
+        .arg("42")
+        .assert()
+        .failure();
+
+    Ok(())
+}
@@ -1,7 +1,13 @@
+use std::io::Write;
+
+use anyhow::{bail, Context};
 use camino::Utf8PathBuf as PathBuf;
 use clap::Parser;
 
-use directip::Message;
+use directip::{Message, MessageReader};
+
+/// Largest possible message, limited by the 2-bytes overall length
+const MAX_MESSAGE_SIZE: usize = 3 + u16::MAX as usize;
 
 /* Possible uses/combinations. Think about it.
  *
@@ -14,6 +20,8 @@ use directip::Message;
  * --payload=hex
  * --payload=bin
  * --payload=int
+ * --hexdump: annotated bytes
+ * --encode: JSON back into binary, such as ncgen
  *
  */
 
@@ -29,35 +37,116 @@ struct Cli {
     #[arg(long)]
     json: bool,
 
+    /// Annotated hexdump of each message
+    #[arg(long)]
+    hexdump: bool,
+
+    /// Encode JSON messages, as given by --json, into binary
+    #[arg(long)]
+    encode: bool,
+
+    /// Output file for --encode, instead of the standard output
+    #[arg(short, long, requires = "encode")]
+    output: Option<PathBuf>,
+
     path: PathBuf,
 }
 
+/// Encode a sequence of JSON messages into concatenated binary messages
+///
+/// Every message is validated first, so nothing is written if any of them
+/// violates the Direct-IP specification.
+fn encode_json(path: PathBuf, output: Option<PathBuf>) -> anyhow::Result<()> {
+    let rdr = std::fs::File::open(&path).with_context(|| format!("Error opening {}", path))?;
+    let rdr = std::io::BufReader::new(rdr);
+
+    let mut buffer = Vec::new();
+    let messages = serde_json::Deserializer::from_reader(rdr).into_iter::<Message>();
+    for (i, msg) in messages.enumerate() {
+        let msg = msg.with_context(|| format!("Invalid JSON for message {}", i + 1))?;
+
+        let violations = msg.validate();
+        if !violations.is_empty() {
+            for v in &violations {
+                eprintln!("Message {}: {}", i + 1, v);
+            }
+            bail!("Message {} violates the Direct-IP specification", i + 1);
+        }
+
+        let mut bytes = vec![0; MAX_MESSAGE_SIZE];
+        let n = msg
+            .encode(&mut bytes)
+            .with_context(|| format!("Failed to encode message {}", i + 1))?;
+        bytes.truncate(n);
+        buffer.extend_from_slice(&bytes);
+    }
+
+    match output {
+        Some(output) => {
+            std::fs::write(&output, &buffer).with_context(|| format!("Error writing {}", output))?
+        }
+        None => std::io::stdout().write_all(&buffer)?,
+    }
+    Ok(())
+}
+
 fn main() {
     let Cli {
         path,
         direction,
         imei,
         json,
+        hexdump,
+        encode,
+        output,
     } = Cli::parse();
 
+    if encode {
+        if let Err(e) = encode_json(path, output) {
+            eprintln!("{:#}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let rdr = std::fs::File::open(path).expect("Error opening file");
+    let rdr = std::io::BufReader::new(rdr);
+
+    // A file might contain multiple concatenated messages
+    let mut failed = false;
+    for msg in MessageReader::new(rdr) {
+        let msg = match msg {
+            Ok(msg) => msg,
+            Err(e) => {
+                eprintln!("Invalid message: {}", e);
+                failed = true;
+                continue;
+            }
+        };
+
+        if imei {
+            match msg.imei() {
+                Some(imei) => println!("{}", imei),
+                None => println!("Unknown"),
+            }
+        } else if direction {
+            println!("{}", msg.message_type());
+        } else if json {
+            println!("{}", serde_json::to_string(&msg).unwrap());
+        } else if hexdump {
+            match msg.hexdump() {
+                Ok(dump) => print!("{}", dump),
+                Err(e) => {
+                    eprintln!("Failed to encode message: {}", e);
+                    failed = true;
+                }
+            }
+        } else {
+            println!("{}", msg);
+        }
+    }
 
-    let msg = Message::from_reader(rdr).unwrap();
-
-    if imei {
-        let output: String = msg
-            .imei()
-            .unwrap()
-            .iter()
-            .map(|x| format!("{:02x}", x))
-            .collect::<Vec<_>>()
-            .join(":");
-        println!("{}", output);
-    } else if direction {
-        println!("{}", msg.message_type());
-    } else if json {
-        println!("{}", serde_json::to_string(&msg).unwrap());
-    } else {
-        dbg!(msg);
+    if failed {
+        std::process::exit(1);
     }
 }
@@ -26,9 +26,7 @@ fn imei() {
         .arg("--imei")
         .arg("tests/data/mt_confirmation.isbd")
         .assert();
-    assert
-        .success()
-        .stdout("00:01:02:03:04:05:06:07:08:09:0a:0b:0c:0d:0e\n");
+    assert.success().stdout("000102030405060708090a0b0c0d0e\n");
 }
 
 #[test]
@@ -43,3 +41,138 @@ fn json() {
         .success()
         .stdout(contains(r#"message_status":{"SuccessfulQueueOrder":42}"#));
 }
+
+#[test]
+fn concatenated() {
+    let mut cmd = Command::cargo_bin("directip-dump").unwrap();
+
+    let assert = cmd
+        .arg("--direction")
+        .arg("tests/data/concatenated.isbd")
+        .assert();
+    assert.success().stdout("MT\nMT\n");
+}
+
+#[test]
+fn corrupted() {
+    let mut cmd = Command::cargo_bin("directip-dump").unwrap();
+
+    let assert = cmd
+        .arg("--direction")
+        .arg("tests/data/corrupted.isbd")
+        .assert();
+    assert
+        .failure()
+        .stdout("MT\nMT\n")
+        .stderr(contains("Unsupported protocol version 255 at byte 31"));
+}
+
+#[test]
+fn describe() {
+    let mut cmd = Command::cargo_bin("directip-dump").unwrap();
+
+    let assert = cmd.arg("tests/data/mt_confirmation.isbd").assert();
+    assert
+        .success()
+        .stdout(contains("IEI 0x44 MT Confirmation (25 bytes)"))
+        .stdout(contains("Message status: SuccessfulQueueOrder(42)"));
+}
+
+#[test]
+fn hexdump() {
+    let mut cmd = Command::cargo_bin("directip-dump").unwrap();
+
+    let assert = cmd
+        .arg("--hexdump")
+        .arg("tests/data/mt_confirmation.isbd")
+        .assert();
+    assert
+        .success()
+        .stdout(contains("0003  44"))
+        .stdout(contains("IEI: MT Confirmation\n"))
+        .stdout(contains("Message status\n"));
+}
+
+#[test]
+fn encode() {
+    let mut cmd = Command::cargo_bin("directip-dump").unwrap();
+
+    let assert = cmd
+        .arg("--encode")
+        .arg("tests/data/mt_confirmation.json")
+        .assert();
+    let expected = std::fs::read("tests/data/mt_confirmation.isbd").unwrap();
+    assert.success().stdout(expected);
+}
+
+#[test]
+fn encode_output() {
+    let output = std::env::temp_dir().join("directip-dump-encode_output.isbd");
+    let mut cmd = Command::cargo_bin("directip-dump").unwrap();
+
+    let assert = cmd
+        .arg("--encode")
+        .arg("--output")
+        .arg(&output)
+        .arg("tests/data/mt_confirmation.json")
+        .assert();
+    assert.success();
+    let expected = std::fs::read("tests/data/mt_confirmation.isbd").unwrap();
+    assert_eq!(std::fs::read(&output).unwrap(), expected);
+    std::fs::remove_file(output).unwrap();
+}
+
+#[test]
+// Messages violating the specification are not encoded
+fn encode_invalid() {
+    let mut cmd = Command::cargo_bin("directip-dump").unwrap();
+
+    let assert = cmd
+        .arg("--encode")
+        .arg("tests/data/duplicate.json")
+        .assert();
+    assert
+        .failure()
+        .stdout("")
+        .stderr(contains("Duplicate IEI 0x44"));
+}
+
+#[test]
+// A message without any element is reported instead of panicking
+fn encode_empty() {
+    let mut cmd = Command::cargo_bin("directip-dump").unwrap();
+
+    let assert = cmd.arg("--encode").arg("tests/data/empty.json").assert();
+    assert
+        .failure()
+        .stdout("")
+        .stderr(contains("Missing Header"));
+}
+
+#[test]
+// A time of session that doesn't fit in the Header is reported instead of panicking
+fn encode_before_1970() {
+    let mut cmd = Command::cargo_bin("directip-dump").unwrap();
+
+    let assert = cmd
+        .arg("--encode")
+        .arg("tests/data/before_1970.json")
+        .assert();
+    assert
+        .failure()
+        .code(1)
+        .stdout("")
+        .stderr(contains("Invalid time of session"));
+}
+
+#[test]
+fn output_requires_encode() {
+    let mut cmd = Command::cargo_bin("directip-dump").unwrap();
+
+    let assert = cmd
+        .arg("--output")
+        .arg("out.isbd")
+        .arg("tests/data/mt_confirmation.isbd")
+        .assert();
+    assert.failure();
+}
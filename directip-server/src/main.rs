@@ -0,0 +1,307 @@
+//! Server receiving MO messages from the Iridium gateway
+//!
+//! The gateway opens a connection for each MO message, sends it, and waits
+//! for the MO Confirmation. Every message is saved in the storage before it
+//! is confirmed, so a confirmed message is never lost. Otherwise, the
+//! gateway retries it later.
+//!
+//! The MOMSN of each modem is followed as well, warning about lost or
+//! repeated messages.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, Context};
+use clap::{ArgAction, Parser};
+use futures::{SinkExt, StreamExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use tokio_util::codec::Framed;
+use tracing::Level;
+
+use directip::codec::DirectIPCodec;
+use directip::mo::MOMessage;
+use directip::sequence::{MomsnTracker, Sequence};
+use directip::Message;
+use directip_storage::Database;
+
+#[derive(Parser)]
+#[command(author, version, about, long_about = None)]
+struct Cli {
+    /// Address to listen on, such as 0.0.0.0:10800
+    #[arg(short, long)]
+    listen: String,
+
+    /// Storage for the received messages: volatile://, filesystem://<path>
+    /// or, with the sqlite feature, sqlite://<path>
+    #[arg(short, long)]
+    storage: String,
+
+    /// Seconds to wait on a connection before dropping it
+    #[arg(long, default_value_t = 30)]
+    timeout: u64,
+
+    /// Sets the level of verbosity
+    #[arg(short, long, action = ArgAction::Count)]
+    verbose: u8,
+}
+
+/// State shared by all connections
+struct Server {
+    db: Database,
+    tracker: Mutex<MomsnTracker>,
+    /// Held while persisting the tracker, which is not locked meanwhile
+    persisting: Mutex<()>,
+    timeout: Duration,
+}
+
+type Connection = Framed<TcpStream, DirectIPCodec>;
+
+impl Server {
+    async fn new(storage: &str, timeout: Duration) -> anyhow::Result<Server> {
+        let db = Database::open(storage)
+            .await
+            .map_err(|e| anyhow!("Failed to open storage {}: {}", storage, e))?;
+        let tracker = db
+            .load_momsn()
+            .await
+            .map_err(|e| anyhow!("Failed to load the MOMSN of each modem: {}", e))?;
+        Ok(Server {
+            db,
+            tracker: Mutex::new(tracker),
+            persisting: Mutex::new(()),
+            timeout,
+        })
+    }
+
+    /// Accept connections, each one handled in its own task
+    async fn serve(self: Arc<Self>, listener: TcpListener) -> anyhow::Result<()> {
+        loop {
+            let (stream, peer) = listener.accept().await?;
+            let server = Arc::clone(&self);
+            tokio::spawn(async move {
+                if let Err(e) = server.handle(stream, peer).await {
+                    tracing::warn!("Connection from {}: {:#}", peer, e);
+                }
+            });
+        }
+    }
+
+    async fn handle(&self, stream: TcpStream, peer: SocketAddr) -> anyhow::Result<()> {
+        tracing::debug!("Connection from {}", peer);
+        let mut connection = Framed::new(stream, DirectIPCodec::new());
+
+        let received = tokio::time::timeout(self.timeout, connection.next())
+            .await
+            .context("Timed out waiting for a message")?;
+        let msg = match received {
+            Some(Ok(Message::MO(msg))) => msg,
+            Some(Ok(Message::MT(_))) => {
+                self.reply(&mut connection, failure()).await?;
+                bail!("Expected an MO message, received an MT message");
+            }
+            Some(Err(e)) => {
+                self.reply(&mut connection, failure()).await?;
+                return Err(e).context("Invalid message");
+            }
+            None => bail!("Connection closed before a complete message"),
+        };
+
+        let acknowledgment = msg.acknowledgment();
+        match msg.header() {
+            Some(header) => {
+                tracing::info!(
+                    "MO message from IMEI {}, MOMSN {}",
+                    header.imei(),
+                    header.momsn()
+                );
+                // Not confirmed unless saved, so that the gateway retries it
+                let saved = self.db.save(Message::MO(msg.clone())).await;
+                if let Err(e) = saved.map_err(|e| e.to_string()) {
+                    self.reply(&mut connection, failure()).await?;
+                    bail!("Failed to save the message: {}", e);
+                }
+                self.track(&msg).await;
+            }
+            None => tracing::warn!("MO message from {} without MO Header", peer),
+        }
+        self.reply(&mut connection, acknowledgment).await
+    }
+
+    /// Follow the MOMSN of the modem, warning about any anomaly
+    async fn track(&self, msg: &MOMessage) {
+        let sequence = match self.tracker.lock().await.track(msg) {
+            Some(sequence) => sequence,
+            None => return,
+        };
+        if sequence.is_anomaly() {
+            let imei = msg.imei().expect("Tracked messages have an MO Header");
+            tracing::warn!("IMEI {}: {}", imei, sequence);
+        }
+        // Nothing changed to persist
+        if matches!(
+            sequence,
+            Sequence::Duplicate(_) | Sequence::OutOfOrder { .. }
+        ) {
+            return;
+        }
+
+        // One write at a time, each with the latest state, so that an older
+        // state never overwrites a newer one.
+        let _persisting = self.persisting.lock().await;
+        let tracker = self.tracker.lock().await.clone();
+        if let Err(e) = self.db.save_momsn(&tracker).await {
+            tracing::error!("Failed to save the MOMSN of each modem: {}", e);
+        }
+    }
+
+    /// Send the MO Confirmation
+    async fn reply(
+        &self,
+        connection: &mut Connection,
+        confirmation: MOMessage,
+    ) -> anyhow::Result<()> {
+        tokio::time::timeout(self.timeout, connection.send(Message::MO(confirmation)))
+            .await
+            .context("Timed out sending the confirmation")??;
+        Ok(())
+    }
+}
+
+/// MO Confirmation of a message that couldn't be accepted
+fn failure() -> MOMessage {
+    MOMessage::builder()
+        .confirmation(false)
+        .build()
+        .expect("A confirmation alone is valid")
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let cli = Cli::parse();
+
+    let level = match cli.verbose {
+        0 => Level::INFO,
+        1 => Level::DEBUG,
+        _ => Level::TRACE,
+    };
+    tracing_subscriber::fmt().with_max_level(level).init();
+
+    let server = Server::new(&cli.storage, Duration::from_secs(cli.timeout)).await?;
+    let listener = TcpListener::bind(&cli.listen)
+        .await
+        .with_context(|| format!("Failed to listen on {}", cli.listen))?;
+    tracing::info!("Listening on {}", listener.local_addr()?);
+
+    Arc::new(server).serve(listener).await
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::SocketAddr;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use futures::{SinkExt, StreamExt};
+    use tokio::net::{TcpListener, TcpStream};
+    use tokio_util::codec::Framed;
+
+    use super::Server;
+    use directip::codec::DirectIPCodec;
+    use directip::mo::{MOMessage, SessionStatus};
+    use directip::Message;
+
+    /// Start a server on any available port
+    async fn start(storage: &str) -> SocketAddr {
+        let server = Server::new(storage, Duration::from_millis(200))
+            .await
+            .unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(Arc::new(server).serve(listener));
+        addr
+    }
+
+    async fn connect(addr: SocketAddr) -> Framed<TcpStream, DirectIPCodec> {
+        Framed::new(
+            TcpStream::connect(addr).await.unwrap(),
+            DirectIPCodec::new(),
+        )
+    }
+
+    fn mo(momsn: u16) -> MOMessage {
+        MOMessage::builder()
+            .cdr_uid(9999)
+            .imei("300234010753376".parse().unwrap())
+            .session_status(SessionStatus::Success)
+            .momsn(momsn)
+            .mtmsn(0)
+            .time_of_session("2000-03-14T12:12:12Z".parse().unwrap())
+            .payload(b"Hello".to_vec())
+            .build()
+            .unwrap()
+    }
+
+    /// Send a message, returning the MO Confirmation status
+    async fn send(addr: SocketAddr, msg: Message) -> bool {
+        let mut connection = connect(addr).await;
+        connection.send(msg).await.unwrap();
+        match connection.next().await {
+            Some(Ok(Message::MO(reply))) => reply.confirmation().unwrap().is_successful(),
+            other => panic!("Expected an MO Confirmation, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn confirm_n_save() {
+        let tmp_dir = tempfile::TempDir::new().unwrap();
+        let storage = format!("filesystem://{}", tmp_dir.path().to_str().unwrap());
+        let addr = start(&storage).await;
+
+        assert!(send(addr, Message::MO(mo(7))).await);
+        assert!(send(addr, Message::MO(mo(8))).await);
+
+        // Possibly within the same second, under the annual directories
+        let saved = tmp_dir.path().join("data").join("300234010753376");
+        let mut files: Vec<_> = std::fs::read_dir(saved)
+            .unwrap()
+            .flat_map(|year| std::fs::read_dir(year.unwrap().path()).unwrap())
+            .map(|entry| entry.unwrap().path())
+            .collect();
+        files.sort();
+        assert_eq!(files.len(), 2);
+        assert_eq!(std::fs::read(&files[0]).unwrap(), mo(7).to_vec());
+        assert_eq!(std::fs::read(&files[1]).unwrap(), mo(8).to_vec());
+        let momsn = std::fs::read_to_string(tmp_dir.path().join("momsn.txt")).unwrap();
+        assert_eq!(momsn.trim(), "300234010753376 8");
+    }
+
+    #[tokio::test]
+    // A message that can't be saved is not confirmed
+    async fn save_failure() {
+        let tmp_dir = tempfile::TempDir::new().unwrap();
+        // A file where the data directory was expected
+        std::fs::write(tmp_dir.path().join("data"), "").unwrap();
+        let storage = format!("filesystem://{}", tmp_dir.path().to_str().unwrap());
+        let addr = start(&storage).await;
+
+        assert!(!send(addr, Message::MO(mo(7))).await);
+        assert!(!tmp_dir.path().join("momsn.txt").exists());
+    }
+
+    #[tokio::test]
+    async fn reject_mt() {
+        let addr = start("volatile://").await;
+        assert!(!send(addr, directip::sample()).await);
+    }
+
+    #[tokio::test]
+    // An idle connection is dropped after the timeout
+    async fn timeout() {
+        let addr = start("volatile://").await;
+        let mut connection = connect(addr).await;
+        let closed = tokio::time::timeout(Duration::from_secs(5), connection.next()).await;
+        assert!(matches!(closed, Ok(None)));
+    }
+}
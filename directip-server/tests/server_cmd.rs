@@ -0,0 +1,43 @@
+use assert_cmd::Command;
+use predicates::str::contains;
+
+#[test]
+// Missing storage
+fn missing_storage() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("directip-server")?;
+
+    cmd.args(["--listen=127.0.0.1:0"])
+        .assert()
+        .failure()
+        .stderr(contains("--storage"));
+
+    Ok(())
+}
+
+#[test]
+// Address that can't be used
+fn invalid_listen() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("directip-server")?;
+
+    cmd.args(["--listen=not-an-address"])
+        .args(["--storage=volatile://"])
+        .assert()
+        .failure()
+        .stderr(contains("Failed to listen on not-an-address"));
+
+    Ok(())
+}
+
+#[test]
+// Storage that isn't known
+fn unknown_storage() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::cargo_bin("directip-server")?;
+
+    cmd.args(["--listen=127.0.0.1:0"])
+        .args(["--storage=mssql://"])
+        .assert()
+        .failure()
+        .stderr(contains("Unknown storage: mssql://"));
+
+    Ok(())
+}
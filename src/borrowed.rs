@@ -0,0 +1,457 @@
+//! Borrowed view of a Message
+//!
+//! A [MessageRef] parses a Direct-IP message directly from a slice of bytes
+//! without copying anything. Only the framing, i.e. the protocol version, the
+//! overall length, and the length of each Information Element, is validated
+//! at parsing, and the fields are decoded when requested. It is intended for
+//! high-throughput processing, such as scanning large archives, where most
+//! messages would be discarded or only a few fields are needed.
+
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+use chrono::{DateTime, TimeZone, Utc};
+
+use crate::error::{Error, Result};
+use crate::mo::SessionStatus;
+use crate::mt::DispositionFlags;
+use crate::{direction, Direction, Imei, Message, Violation};
+
+/// A Message borrowed from a slice of bytes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MessageRef<'a> {
+    direction: Direction,
+    bytes: &'a [u8],
+}
+
+impl<'a> MessageRef<'a> {
+    /// Parse a Message from a slice of bytes
+    ///
+    /// The slice might be longer than the message, for instance when
+    /// multiple messages are concatenated. In that case, only the first
+    /// message is considered, and [MessageRef::total_size] gives where the
+    /// next one starts.
+    pub fn from_bytes(buffer: &'a [u8]) -> Result<Self> {
+        // Protocol version, message length, and the first IEI
+        if buffer.len() < 4 {
+            return Err(Error::eof());
+        }
+        let version = buffer[0];
+        if version != 1 {
+            return Err(Error::UnsupportedVersion { version, offset: 0 });
+        }
+        let length = usize::from(u16::from_be_bytes([buffer[1], buffer[2]]));
+        let direction = direction(buffer[3]).ok_or(Error::UnknownIEI {
+            iei: buffer[3],
+            offset: 3,
+        })?;
+
+        let mut n = 0;
+        while n < length {
+            let offset = 3 + n;
+            let iei = match buffer.get(offset) {
+                Some(iei) => *iei,
+                // Input ended before the next IEI
+                None => {
+                    return Err(Error::MessageLengthMismatch {
+                        declared: length,
+                        actual: n,
+                    })
+                }
+            };
+            let size = match buffer.get(offset + 1..offset + 3) {
+                Some(len) => 3 + usize::from(u16::from_be_bytes([len[0], len[1]])),
+                None => return Err(Error::TruncatedElement { iei, offset }),
+            };
+            if buffer.len() < offset + size {
+                return Err(Error::TruncatedElement { iei, offset });
+            }
+            n += size;
+        }
+        if n != length {
+            return Err(Error::MessageLengthMismatch {
+                declared: length,
+                actual: n,
+            });
+        }
+
+        Ok(MessageRef {
+            direction,
+            bytes: &buffer[..3 + length],
+        })
+    }
+
+    pub(crate) fn direction(&self) -> Direction {
+        self.direction
+    }
+
+    /// Verify this Message against the Direct-IP specification
+    ///
+    /// Returns all violations found, such as duplicate elements or invalid
+    /// lengths, thus an empty list for a compliant message.
+    pub fn validate(&self) -> Vec<Violation> {
+        crate::validation::validate(self)
+    }
+
+    /// Identify the type of a Message between MO or MT
+    pub fn message_type(&self) -> String {
+        match self.direction {
+            Direction::MO => "MO".to_string(),
+            Direction::MT => "MT".to_string(),
+        }
+    }
+
+    /// Total size of the Message in bytes
+    ///
+    /// This includes the protocol version and the overall message length.
+    pub fn total_size(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// The bytes of this Message
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Iterate over the Information Elements
+    pub fn elements(&self) -> Elements<'a> {
+        Elements {
+            bytes: &self.bytes[3..],
+        }
+    }
+
+    /// First Information Element with the given identifier
+    pub fn element(&self, iei: u8) -> Option<ElementRef<'a>> {
+        self.elements().find(|e| e.identifier() == iei)
+    }
+
+    /// Extract the IMEI from a Message
+    ///
+    /// The IMEI is available in the MO Header, the MT Header, or the MT
+    /// Confirmation, always right after a 4-bytes field.
+    pub fn imei(&self) -> Option<Imei> {
+        let iei: &[u8] = match self.direction {
+            Direction::MO => &[0x01],
+            Direction::MT => &[0x41, 0x44],
+        };
+        self.elements()
+            .find(|e| iei.contains(&e.identifier()))
+            .and_then(|e| e.body().get(4..19))
+            .and_then(|imei| imei.try_into().ok())
+    }
+
+    /// The MO Header, if present with the expected length
+    pub fn mo_header(&self) -> Option<MOHeaderRef<'a>> {
+        if self.direction != Direction::MO {
+            return None;
+        }
+        self.element(0x01)
+            .map(|e| e.body())
+            .filter(|body| body.len() == MOHeaderRef::LEN)
+            .map(|bytes| MOHeaderRef { bytes })
+    }
+
+    /// The MT Header, if present with the expected length
+    pub fn mt_header(&self) -> Option<MTHeaderRef<'a>> {
+        if self.direction != Direction::MT {
+            return None;
+        }
+        self.element(0x41)
+            .map(|e| e.body())
+            .filter(|body| body.len() == MTHeaderRef::LEN)
+            .map(|bytes| MTHeaderRef { bytes })
+    }
+
+    /// The payload, if available
+    pub fn payload(&self) -> Option<&'a [u8]> {
+        let iei = match self.direction {
+            Direction::MO => 0x02,
+            Direction::MT => 0x42,
+        };
+        self.element(iei).map(|e| e.body())
+    }
+
+    /// Parse into an owned Message
+    ///
+    /// This fully decodes each Information Element, thus it might fail for
+    /// invalid contents not verified by [MessageRef::from_bytes].
+    pub fn to_message(&self) -> Result<Message> {
+        Message::from_bytes(self.bytes)
+    }
+}
+
+impl<'a> TryFrom<MessageRef<'a>> for Message {
+    type Error = Error;
+
+    fn try_from(msg: MessageRef<'a>) -> Result<Self> {
+        msg.to_message()
+    }
+}
+
+/// An Information Element borrowed from a Message
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ElementRef<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ElementRef<'a> {
+    /// Information Element Identifier
+    pub fn identifier(&self) -> u8 {
+        self.bytes[0]
+    }
+
+    /// Length field, i.e. the size of the body
+    pub fn len(&self) -> u16 {
+        u16::from_be_bytes([self.bytes[1], self.bytes[2]])
+    }
+
+    /// True if the element has no body
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Contents of the element, after the identifier and length fields
+    pub fn body(&self) -> &'a [u8] {
+        &self.bytes[3..]
+    }
+
+    /// The bytes of this element, including identifier and length fields
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+fn be_u16(bytes: &[u8]) -> u16 {
+    u16::from_be_bytes([bytes[0], bytes[1]])
+}
+
+fn be_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+/// An MO Header borrowed from a Message
+///
+/// Each field is decoded when requested, so a field with an invalid value
+/// fails only when accessed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MOHeaderRef<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> MOHeaderRef<'a> {
+    /// Size of the MO Header contents
+    const LEN: usize = 28;
+
+    /// CDR Reference, a unique ID for each call data record
+    pub fn cdr_uid(&self) -> u32 {
+        be_u32(&self.bytes[0..4])
+    }
+
+    pub fn imei(&self) -> Imei {
+        let imei: [u8; 15] = self.bytes[4..19].try_into().expect("Fixed size slice");
+        Imei::from(imei)
+    }
+
+    pub fn session_status(&self) -> Result<SessionStatus> {
+        SessionStatus::decode(&self.bytes[19])
+    }
+
+    /// MO Message Sequence Number
+    pub fn momsn(&self) -> u16 {
+        be_u16(&self.bytes[20..22])
+    }
+
+    /// MT Message Sequence Number
+    pub fn mtmsn(&self) -> u16 {
+        be_u16(&self.bytes[22..24])
+    }
+
+    pub fn time_of_session(&self) -> Result<DateTime<Utc>> {
+        let t = be_u32(&self.bytes[24..28]);
+        Utc.timestamp_opt(t.into(), 0)
+            .single()
+            .ok_or(Error::InvalidTimeOfSession(t.into()))
+    }
+}
+
+/// An MT Header borrowed from a Message
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MTHeaderRef<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> MTHeaderRef<'a> {
+    /// Size of the MT Header contents
+    const LEN: usize = 21;
+
+    /// Unique Client Message ID
+    pub fn client_msg_id(&self) -> u32 {
+        be_u32(&self.bytes[0..4])
+    }
+
+    pub fn imei(&self) -> Imei {
+        let imei: [u8; 15] = self.bytes[4..19].try_into().expect("Fixed size slice");
+        Imei::from(imei)
+    }
+
+    pub fn disposition_flags(&self) -> DispositionFlags {
+        DispositionFlags::decode(be_u16(&self.bytes[19..21]))
+    }
+}
+
+/// Iterator over the Information Elements of a [MessageRef]
+#[derive(Clone, Debug)]
+pub struct Elements<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Iterator for Elements<'a> {
+    type Item = ElementRef<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // Framing was already validated by MessageRef::from_bytes()
+        if self.bytes.len() < 3 {
+            return None;
+        }
+        let size = 3 + usize::from(u16::from_be_bytes([self.bytes[1], self.bytes[2]]));
+        let (element, rest) = self.bytes.split_at(size);
+        self.bytes = rest;
+        Some(ElementRef { bytes: element })
+    }
+}
+
+#[cfg(test)]
+mod test_message_ref {
+    use super::{Error, MessageRef};
+    use crate::Message;
+
+    const MO: [u8; 49] = [
+        0x01, 0x00, 0x2e, 0x01, 0x00, 0x1c, 0x00, 0x00, 0x27, 0x0f, 0x00, 0x01, 0x02, 0x03, 0x04,
+        0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x00, 0x03, 0xe7, 0x00, 0x6f,
+        0x38, 0xce, 0x2c, 0x9c, 0x02, 0x00, 0x0c, 0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x20, 0x57, 0x6f,
+        0x72, 0x6c, 0x64, 0x21,
+    ];
+
+    #[test]
+    fn mo() {
+        let msg = MessageRef::from_bytes(&MO).unwrap();
+        assert_eq!(msg.message_type(), "MO");
+        assert_eq!(msg.total_size(), MO.len());
+        assert_eq!(msg.elements().count(), 2);
+        assert_eq!(
+            msg.imei(),
+            Some([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14].into())
+        );
+        assert_eq!(msg.payload(), Some("Hello World!".as_bytes()));
+    }
+
+    #[test]
+    fn mo_header() {
+        let msg = MessageRef::from_bytes(&MO).unwrap();
+        let owned = match msg.to_message().unwrap() {
+            Message::MO(m) => m,
+            Message::MT(_) => unreachable!(),
+        };
+        let expected = owned.header().unwrap();
+
+        let header = msg.mo_header().unwrap();
+        assert_eq!(header.cdr_uid(), expected.cdr_uid());
+        assert_eq!(header.imei(), expected.imei());
+        assert_eq!(&header.session_status().unwrap(), expected.session_status());
+        assert_eq!(header.momsn(), expected.momsn());
+        assert_eq!(header.mtmsn(), expected.mtmsn());
+        assert_eq!(
+            header.time_of_session().unwrap(),
+            expected.time_of_session()
+        );
+        assert!(msg.mt_header().is_none());
+    }
+
+    #[test]
+    fn mt_header() {
+        let msg = crate::mt::MTMessage::builder()
+            .client_msg_id(9999)
+            .imei("300234010753376".parse().unwrap())
+            .flush_queue(true)
+            .build()
+            .unwrap();
+        let buffer = msg.to_vec();
+        let borrowed = MessageRef::from_bytes(&buffer).unwrap();
+
+        let header = borrowed.mt_header().unwrap();
+        assert_eq!(header.client_msg_id(), 9999);
+        assert_eq!(header.imei(), msg.imei().unwrap());
+        assert_eq!(
+            &header.disposition_flags(),
+            msg.disposition_flags().unwrap()
+        );
+        assert!(header.disposition_flags().flush_queue());
+        assert!(borrowed.mo_header().is_none());
+    }
+
+    #[test]
+    fn mt_confirmation() {
+        let buffer = crate::sample().to_vec();
+        let msg = MessageRef::from_bytes(&buffer).unwrap();
+        assert_eq!(msg.message_type(), "MT");
+        assert_eq!(
+            msg.imei(),
+            Some([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14].into())
+        );
+        assert!(msg.payload().is_none());
+    }
+
+    #[test]
+    fn to_message() {
+        let msg = MessageRef::from_bytes(&MO).unwrap();
+        let owned: Message = msg.try_into().unwrap();
+        assert_eq!(owned.message_type(), "MO");
+        assert_eq!(owned.to_vec(), MO);
+    }
+
+    #[test]
+    // Only the first message is borrowed from a longer buffer
+    fn concatenated() {
+        let mut buffer = MO.to_vec();
+        buffer.extend_from_slice(&MO);
+        let msg = MessageRef::from_bytes(&buffer).unwrap();
+        assert_eq!(msg.as_bytes(), MO);
+        let next = MessageRef::from_bytes(&buffer[msg.total_size()..]).unwrap();
+        assert_eq!(next.as_bytes(), MO);
+    }
+
+    #[test]
+    fn truncated() {
+        assert!(matches!(
+            MessageRef::from_bytes(&MO[..40]),
+            Err(Error::TruncatedElement {
+                iei: 0x02,
+                offset: 34
+            })
+        ));
+        assert!(matches!(
+            MessageRef::from_bytes(&MO[..34]),
+            Err(Error::MessageLengthMismatch {
+                declared: 46,
+                actual: 31
+            })
+        ));
+        assert!(matches!(
+            MessageRef::from_bytes(&MO[..2]),
+            Err(Error::UnexpectedEof)
+        ));
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let mut buffer = MO;
+        buffer[0] = 2;
+        assert!(matches!(
+            MessageRef::from_bytes(&buffer),
+            Err(Error::UnsupportedVersion {
+                version: 2,
+                offset: 0
+            })
+        ));
+    }
+}
@@ -0,0 +1,136 @@
+//! Tokio codec for Direct-IP messages
+//!
+//! Frames a stream of bytes, such as a TCP connection, into Direct-IP
+//! messages using the protocol header, i.e. the protocol revision number
+//! followed by the overall message length. Partial reads are buffered until
+//! a complete message is available.
+
+use bytes::{Buf, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::error::Error;
+use crate::Message;
+
+/// Size of the protocol header: revision number and overall message length
+const HEADER_LEN: usize = 3;
+
+#[derive(Clone, Debug, Default)]
+/// Encode and decode Direct-IP messages on a stream
+pub struct DirectIPCodec;
+
+impl DirectIPCodec {
+    pub fn new() -> DirectIPCodec {
+        DirectIPCodec
+    }
+}
+
+impl Decoder for DirectIPCodec {
+    type Item = Message;
+    type Error = Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Message>, Error> {
+        if src.is_empty() {
+            return Ok(None);
+        }
+        // Fail early instead of waiting for the rest of an invalid message
+        let version = src[0];
+        if version != 1 {
+            return Err(Error::UnsupportedVersion { version, offset: 0 });
+        }
+        if src.len() < HEADER_LEN {
+            return Ok(None);
+        }
+
+        let length = usize::from(u16::from_be_bytes([src[1], src[2]]));
+        let total_size = HEADER_LEN + length;
+        if src.len() < total_size {
+            src.reserve(total_size - src.len());
+            return Ok(None);
+        }
+
+        let frame = src.split_to(total_size);
+        Message::from_reader(frame.reader()).map(Some)
+    }
+}
+
+impl Encoder<Message> for DirectIPCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: Message, dst: &mut BytesMut) -> Result<(), Error> {
+        self.encode(&item, dst)
+    }
+}
+
+impl Encoder<&Message> for DirectIPCodec {
+    type Error = Error;
+
+    fn encode(&mut self, item: &Message, dst: &mut BytesMut) -> Result<(), Error> {
+        dst.extend_from_slice(&item.to_vec());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_codec {
+    use bytes::BytesMut;
+    use tokio_util::codec::{Decoder, Encoder};
+
+    use super::DirectIPCodec;
+    use crate::error::Error;
+    use crate::sample;
+
+    #[test]
+    fn roundtrip() {
+        let mut codec = DirectIPCodec::new();
+        let mut buffer = BytesMut::new();
+        codec.encode(sample(), &mut buffer).unwrap();
+        assert_eq!(buffer, sample().to_vec());
+
+        let msg = codec.decode(&mut buffer).unwrap().unwrap();
+        assert_eq!(msg.to_vec(), sample().to_vec());
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    // Bytes arriving one at a time
+    fn partial_reads() {
+        let mut codec = DirectIPCodec::new();
+        let bytes = sample().to_vec();
+        let mut buffer = BytesMut::new();
+        for b in &bytes[..bytes.len() - 1] {
+            buffer.extend_from_slice(&[*b]);
+            assert!(codec.decode(&mut buffer).unwrap().is_none());
+        }
+        buffer.extend_from_slice(&bytes[bytes.len() - 1..]);
+        let msg = codec.decode(&mut buffer).unwrap().unwrap();
+        assert_eq!(msg.to_vec(), bytes);
+    }
+
+    #[test]
+    // Multiple messages in the same read
+    fn consecutive() {
+        let mut codec = DirectIPCodec::new();
+        let mut buffer = BytesMut::new();
+        codec.encode(&sample(), &mut buffer).unwrap();
+        codec.encode(&sample(), &mut buffer).unwrap();
+        buffer.extend_from_slice(&[0x01]);
+
+        assert!(codec.decode(&mut buffer).unwrap().is_some());
+        assert!(codec.decode(&mut buffer).unwrap().is_some());
+        assert!(codec.decode(&mut buffer).unwrap().is_none());
+        assert_eq!(buffer.len(), 1);
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let mut codec = DirectIPCodec::new();
+        let mut buffer = BytesMut::from(&[0x02][..]);
+        assert!(matches!(
+            codec.decode(&mut buffer),
+            Err(Error::UnsupportedVersion {
+                version: 2,
+                offset: 0
+            })
+        ));
+    }
+}
@@ -0,0 +1,89 @@
+//! Helpers for the human-readable description of Messages
+//!
+//! Each Information Element implements Display as a list of `field: value`
+//! lines. Those are indented under the element, and the elements under the
+//! message, resulting in a report in the spirit of ncdump.
+
+use alloc::string::ToString;
+use core::fmt;
+
+/// Maximum number of bytes shown in a payload preview
+const PREVIEW_LEN: usize = 64;
+
+/// Write each line of `content` on a new line with the given indentation
+pub(crate) fn write_indented(
+    f: &mut fmt::Formatter<'_>,
+    content: &dyn fmt::Display,
+    indent: &str,
+) -> fmt::Result {
+    for line in content.to_string().lines() {
+        write!(f, "\n{}{}", indent, line)?;
+    }
+    Ok(())
+}
+
+/// Preview a sequence of bytes as ASCII text if printable, or hex otherwise
+///
+/// Long contents are truncated, reporting how many bytes were omitted.
+pub(crate) fn write_preview(f: &mut fmt::Formatter<'_>, bytes: &[u8]) -> fmt::Result {
+    let n = bytes.len().min(PREVIEW_LEN);
+    let preview = &bytes[..n];
+    if !preview.is_empty() && preview.iter().all(|b| b.is_ascii_graphic() || *b == b' ') {
+        // Safe, since all bytes are printable ASCII
+        write!(f, "\"{}\"", core::str::from_utf8(preview).unwrap())?;
+    } else {
+        for (i, b) in preview.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{:02x}", b)?;
+        }
+    }
+    if bytes.len() > n {
+        write!(f, " ... ({} more bytes)", bytes.len() - n)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_describe {
+    use super::{write_indented, write_preview};
+    use std::fmt;
+
+    struct Preview<'a>(&'a [u8]);
+
+    impl fmt::Display for Preview<'_> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write_preview(f, self.0)
+        }
+    }
+
+    #[test]
+    fn preview_ascii() {
+        assert_eq!(Preview(b"Hello World!").to_string(), "\"Hello World!\"");
+    }
+
+    #[test]
+    fn preview_binary() {
+        assert_eq!(Preview(&[0x80, 0x00, 0x42]).to_string(), "80 00 42");
+        assert_eq!(Preview(b"Hello\n").to_string(), "48 65 6c 6c 6f 0a");
+    }
+
+    #[test]
+    fn preview_truncated() {
+        let preview = Preview(&[b'a'; 70]).to_string();
+        assert!(preview.ends_with("\" ... (6 more bytes)"));
+    }
+
+    #[test]
+    fn indented() {
+        struct Lines;
+        impl fmt::Display for Lines {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "First")?;
+                write_indented(f, &"a: 1\nb: 2", "  ")
+            }
+        }
+        assert_eq!(Lines.to_string(), "First\n  a: 1\n  b: 2");
+    }
+}
@@ -0,0 +1,223 @@
+//! Field-level differences between two Messages
+//!
+//! Information Elements are matched by their identifier and compared field
+//! by field, following the same layout used by the hexdump. Thus, the
+//! comparison is on the encoded bytes, as transmitted.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use crate::error::Result;
+use crate::InformationElement;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A difference between two Messages
+///
+/// For an Information Element present in only one of the messages, `field`
+/// is None and the other side is None as well.
+pub struct Difference {
+    pub iei: u8,
+    pub element: &'static str,
+    pub field: Option<&'static str>,
+    pub left: Option<Vec<u8>>,
+    pub right: Option<Vec<u8>>,
+}
+
+fn write_hex(f: &mut fmt::Formatter<'_>, bytes: &Option<Vec<u8>>) -> fmt::Result {
+    match bytes {
+        None => write!(f, "(none)"),
+        Some(bytes) => bytes.iter().try_for_each(|b| write!(f, "{:02x}", b)),
+    }
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "IEI 0x{:02x} {}", self.iei, self.element)?;
+        match self.field {
+            Some(field) => write!(f, ", {}: ", field)?,
+            None if self.left.is_some() => write!(f, ", only in the first message: ")?,
+            None => write!(f, ", only in the second message: ")?,
+        }
+        write_hex(f, &self.left)?;
+        write!(f, " -> ")?;
+        write_hex(f, &self.right)
+    }
+}
+
+/// An Information Element split in its labelled fields
+pub(crate) struct ElementFields {
+    iei: u8,
+    name: &'static str,
+    contents: Vec<u8>,
+    fields: Vec<(&'static str, usize)>,
+}
+
+impl ElementFields {
+    pub(crate) fn new<E: InformationElement>(name: &'static str, element: &E) -> Result<Self> {
+        let mut contents = element.try_to_vec()?;
+        contents.drain(..3);
+        Ok(ElementFields {
+            iei: element.identifier(),
+            name,
+            contents,
+            fields: element.fields(),
+        })
+    }
+
+    /// Each field with its bytes
+    fn split(&self) -> impl Iterator<Item = (&'static str, &[u8])> + '_ {
+        let mut rest = self.contents.as_slice();
+        self.fields.iter().map(move |(label, len)| {
+            let (field, tail) = rest.split_at((*len).min(rest.len()));
+            rest = tail;
+            (*label, field)
+        })
+    }
+}
+
+/// Compare the Information Elements of two messages
+///
+/// Repeated identifiers, which are not valid but possible to parse, are
+/// matched in order of occurrence.
+pub(crate) fn diff(left: Vec<ElementFields>, right: Vec<ElementFields>) -> Vec<Difference> {
+    let mut differences = Vec::new();
+    let mut right: Vec<Option<ElementFields>> = right.into_iter().map(Some).collect();
+
+    for l in left {
+        let matched = right
+            .iter_mut()
+            .find(|r| r.as_ref().is_some_and(|r| r.iei == l.iei))
+            .and_then(Option::take);
+        let r = match matched {
+            Some(r) => r,
+            None => {
+                differences.push(Difference {
+                    iei: l.iei,
+                    element: l.name,
+                    field: None,
+                    left: Some(l.contents),
+                    right: None,
+                });
+                continue;
+            }
+        };
+        if l.contents == r.contents {
+            continue;
+        }
+        if l.fields.len() != r.fields.len() {
+            differences.push(Difference {
+                iei: l.iei,
+                element: l.name,
+                field: Some("Contents"),
+                left: Some(l.contents),
+                right: Some(r.contents),
+            });
+            continue;
+        }
+        for ((label, a), (_, b)) in l.split().zip(r.split()) {
+            if a != b {
+                differences.push(Difference {
+                    iei: l.iei,
+                    element: l.name,
+                    field: Some(label),
+                    left: Some(a.to_vec()),
+                    right: Some(b.to_vec()),
+                });
+            }
+        }
+    }
+
+    for r in right.into_iter().flatten() {
+        differences.push(Difference {
+            iei: r.iei,
+            element: r.name,
+            field: None,
+            left: None,
+            right: Some(r.contents),
+        });
+    }
+    differences
+}
+
+#[cfg(test)]
+mod test_diff {
+    use super::Difference;
+    use crate::mo::{MOMessage, MOMessageBuilder, SessionStatus};
+    use crate::Message;
+
+    fn builder() -> MOMessageBuilder {
+        MOMessage::builder()
+            .cdr_uid(9999)
+            .imei("300234010753376".parse().unwrap())
+            .session_status(SessionStatus::Success)
+            .momsn(12)
+            .mtmsn(0)
+            .time_of_session("2000-03-14T12:12:12Z".parse().unwrap())
+            .payload(b"Hello".to_vec())
+    }
+
+    fn message(builder: MOMessageBuilder) -> Message {
+        Message::MO(builder.build().unwrap())
+    }
+
+    #[test]
+    fn identical() {
+        let msg = message(builder());
+        assert!(msg.diff(&msg.clone()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn field() {
+        let archived = message(builder());
+        let resent = message(builder().momsn(13));
+        assert_eq!(
+            archived.diff(&resent).unwrap(),
+            [Difference {
+                iei: 0x01,
+                element: "MO Header",
+                field: Some("MOMSN"),
+                left: Some(vec![0x00, 0x0c]),
+                right: Some(vec![0x00, 0x0d]),
+            }]
+        );
+        assert_eq!(
+            archived.diff(&resent).unwrap()[0].to_string(),
+            "IEI 0x01 MO Header, MOMSN: 000c -> 000d"
+        );
+    }
+
+    #[test]
+    fn payload_length() {
+        let left = message(builder());
+        let right = message(builder().payload(b"Hello World".to_vec()));
+        let differences = left.diff(&right).unwrap();
+        assert_eq!(differences.len(), 1);
+        assert_eq!(differences[0].field, Some("Payload"));
+    }
+
+    #[test]
+    fn missing_element() {
+        let left = message(builder());
+        let right = message(builder().location(15.5, -38.25, 10));
+
+        let differences = left.diff(&right).unwrap();
+        assert_eq!(differences.len(), 1);
+        assert_eq!(differences[0].iei, 0x03);
+        assert_eq!(differences[0].field, None);
+        assert_eq!(differences[0].left, None);
+        assert!(differences[0]
+            .to_string()
+            .starts_with("IEI 0x03 MO Location Information, only in the second message"));
+
+        let differences = right.diff(&left).unwrap();
+        assert_eq!(differences[0].right, None);
+    }
+
+    #[test]
+    // Different directions share no Information Element
+    fn direction() {
+        let differences = message(builder()).diff(&crate::sample()).unwrap();
+        assert_eq!(differences.len(), 3);
+        assert!(differences.iter().all(|d| d.field.is_none()));
+    }
+}
@@ -0,0 +1,312 @@
+//! Serde representations for sequences of bytes
+//!
+//! By default, serde serializes bytes as an array of integers in
+//! human-readable formats such as JSON. These modules instead use a string,
+//! hexadecimal or base64, while binary formats keep the compact bytes. They
+//! can be used on any field with `#[serde(with = "directip::encoding::hex")]`.
+//!
+//! The payloads of Messages are always serialized in hexadecimal, regardless
+//! of the enabled features. The `serde-base64` feature only adds the
+//! [base64] module, for fields of other types. On deserialization, arrays of
+//! integers are still accepted, so that previously serialized Messages can be
+//! read.
+
+use alloc::vec::Vec;
+use core::fmt;
+
+use serde::de::{self, SeqAccess, Visitor};
+use serde::Deserializer;
+
+/// Maximum number of bytes, as expected on deserialization
+struct AtMost(usize);
+
+impl de::Expected for AtMost {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "at most {} bytes", self.0)
+    }
+}
+
+/// Hexadecimal contents no longer than `limit`, rejected instead of failing
+/// later on encoding
+fn deserialize_bounded<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    limit: usize,
+) -> Result<Vec<u8>, D::Error> {
+    let bytes = hex::deserialize(deserializer)?;
+    if bytes.len() > limit {
+        return Err(de::Error::invalid_length(bytes.len(), &AtMost(limit)));
+    }
+    Ok(bytes)
+}
+
+/// Contents of an unknown Information Element, as a hexadecimal string
+///
+/// The length of an element is encoded in 2 bytes, thus anything longer
+/// than 65535 bytes is rejected.
+pub(crate) mod element {
+    use alloc::vec::Vec;
+
+    use serde::Deserializer;
+
+    pub(crate) use super::hex::serialize;
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        super::deserialize_bounded(deserializer, usize::from(u16::MAX))
+    }
+}
+
+/// MO Payload, as a hexadecimal string of at most 1960 bytes
+pub(crate) mod mo_payload {
+    use alloc::vec::Vec;
+
+    use serde::Deserializer;
+
+    pub(crate) use super::hex::serialize;
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        super::deserialize_bounded(deserializer, crate::mo::MAX_PAYLOAD_LEN)
+    }
+}
+
+/// MT Payload, as a hexadecimal string of at most 1890 bytes
+pub(crate) mod mt_payload {
+    use alloc::vec::Vec;
+
+    use serde::Deserializer;
+
+    pub(crate) use super::hex::serialize;
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<u8>, D::Error> {
+        super::deserialize_bounded(deserializer, crate::mt::MAX_PAYLOAD_LEN)
+    }
+}
+
+/// Encoded coordinate of a Location, serialized as a [crate::mo::Coordinate]
+///
+/// Coordinates not valid, kept as received, are serialized in decimal
+/// degrees nonetheless, but fail on deserialization.
+pub(crate) mod coordinate {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::mo::Coordinate;
+
+    pub(crate) fn serialize<S: Serializer>(
+        buffer: &[u8; 7],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        Coordinate::decode_unchecked(buffer).serialize(serializer)
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<[u8; 7], D::Error> {
+        Ok(Coordinate::deserialize(deserializer)?.encode())
+    }
+}
+
+/// Information Elements of a Message
+///
+/// The overall length of a Message is encoded in 2 bytes as well, thus
+/// elements adding up to more than 65535 bytes are rejected.
+pub(crate) fn deserialize_elements<'de, D, E>(deserializer: D) -> Result<Vec<E>, D::Error>
+where
+    D: Deserializer<'de>,
+    E: serde::Deserialize<'de> + crate::InformationElement,
+{
+    let elements = <Vec<E> as serde::Deserialize>::deserialize(deserializer)?;
+    let length: usize = elements.iter().map(|e| e.total_size()).sum();
+    if length > usize::from(u16::MAX) {
+        return Err(de::Error::invalid_length(
+            length,
+            &"elements adding up to at most 65535 bytes",
+        ));
+    }
+    Ok(elements)
+}
+
+/// Visitor accepting a string, bytes, or an array of integers
+struct BytesVisitor {
+    expecting: &'static str,
+    decode: fn(&str) -> Option<Vec<u8>>,
+}
+
+impl<'de> Visitor<'de> for BytesVisitor {
+    type Value = Vec<u8>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.expecting)
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        (self.decode)(v).ok_or_else(|| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+        Ok(v.to_vec())
+    }
+
+    fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<Self::Value, E> {
+        Ok(v)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+        while let Some(b) = seq.next_element()? {
+            bytes.push(b);
+        }
+        Ok(bytes)
+    }
+}
+
+fn deserialize_with<'de, D: Deserializer<'de>>(
+    deserializer: D,
+    visitor: BytesVisitor,
+) -> Result<Vec<u8>, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_any(visitor)
+    } else {
+        deserializer.deserialize_byte_buf(visitor)
+    }
+}
+
+/// Bytes as a lowercase hexadecimal string, such as `"48656c6c6f"`
+pub mod hex {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use core::fmt::Write;
+
+    use serde::{Deserializer, Serializer};
+
+    use super::BytesVisitor;
+
+    pub(crate) fn encode(bytes: &[u8]) -> String {
+        let mut s = String::with_capacity(2 * bytes.len());
+        for b in bytes {
+            // Writing to a String never fails
+            let _ = write!(s, "{:02x}", b);
+        }
+        s
+    }
+
+    pub(crate) fn decode(s: &str) -> Option<Vec<u8>> {
+        if s.len() % 2 != 0 || !s.is_ascii() {
+            return None;
+        }
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+            .collect()
+    }
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&encode(bytes))
+        } else {
+            serializer.serialize_bytes(bytes)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let visitor = BytesVisitor {
+            expecting: "a hexadecimal string",
+            decode,
+        };
+        super::deserialize_with(deserializer, visitor)
+    }
+}
+
+/// Bytes as a standard, padded, base64 string, such as `"SGVsbG8="`
+#[cfg(feature = "serde-base64")]
+pub mod base64 {
+    use alloc::vec::Vec;
+
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+    use serde::{Deserializer, Serializer};
+
+    use super::BytesVisitor;
+
+    fn decode(s: &str) -> Option<Vec<u8>> {
+        STANDARD.decode(s).ok()
+    }
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&STANDARD.encode(bytes))
+        } else {
+            serializer.serialize_bytes(bytes)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let visitor = BytesVisitor {
+            expecting: "a base64 string",
+            decode,
+        };
+        super::deserialize_with(deserializer, visitor)
+    }
+}
+
+#[cfg(test)]
+mod test_encoding {
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Hex(#[serde(with = "super::hex")] Vec<u8>);
+
+    #[test]
+    fn hex_json() {
+        let value = Hex(vec![0x00, 0x42, 0xff]);
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, "\"0042ff\"");
+        assert_eq!(serde_json::from_str::<Hex>(&json).unwrap(), value);
+    }
+
+    #[test]
+    fn hex_uppercase() {
+        let value: Hex = serde_json::from_str("\"0042FF\"").unwrap();
+        assert_eq!(value, Hex(vec![0x00, 0x42, 0xff]));
+    }
+
+    #[test]
+    fn hex_invalid() {
+        assert!(serde_json::from_str::<Hex>("\"004\"").is_err());
+        assert!(serde_json::from_str::<Hex>("\"0g\"").is_err());
+        assert!(serde_json::from_str::<Hex>("\"é0\"").is_err());
+    }
+
+    #[test]
+    // Also valid base64, but elements are always hexadecimal
+    fn element_hex() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Element(#[serde(with = "super::element")] Vec<u8>);
+
+        let value: Element = serde_json::from_str("\"48656c6c\"").unwrap();
+        assert_eq!(value, Element(b"Hell".to_vec()));
+        assert_eq!(serde_json::to_string(&value).unwrap(), "\"48656c6c\"");
+    }
+
+    #[test]
+    // Arrays of integers, as previously serialized, are still accepted
+    fn legacy_array() {
+        let value: Hex = serde_json::from_str("[0, 66, 255]").unwrap();
+        assert_eq!(value, Hex(vec![0x00, 0x42, 0xff]));
+    }
+
+    #[cfg(feature = "serde-base64")]
+    #[test]
+    fn base64_json() {
+        #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+        struct Base64(#[serde(with = "super::base64")] Vec<u8>);
+
+        let value = Base64(b"Hello".to_vec());
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, "\"SGVsbG8=\"");
+        assert_eq!(serde_json::from_str::<Base64>(&json).unwrap(), value);
+        assert!(serde_json::from_str::<Base64>("\"SGVsbG8\"").is_err());
+    }
+}
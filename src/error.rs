@@ -1,30 +1,256 @@
-use thiserror::Error;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt;
 
 #[allow(dead_code)]
-#[derive(Debug, Error)]
+#[derive(Debug)]
 /// Possible errors for Iridium Direct-IP protocol
 pub enum Error {
-    #[error(transparent)]
-    IO(#[from] std::io::Error),
+    #[cfg(feature = "std")]
+    IO(std::io::Error),
 
-    #[error(transparent)]
-    UninitializedFieldError(#[from] derive_builder::UninitializedFieldError),
+    /// Input ended before the expected number of bytes
+    UnexpectedEof,
+
+    UninitializedFieldError(derive_builder::UninitializedFieldError),
 
     /// Not the expected Information Element type
-    #[error("Wrong IEI for {0}. Expected {1} instead of {2}")]
     WrongIEType(String, u8, u8),
 
     /// Invalid status for MT::Confirmation::MessageStatus.
-    #[error("Invalid MessageStatus: {0}")]
     InvalidMessageStatus(i16),
 
     /// Invalid status for MO::Header::SeessionStatus.
-    #[error("Invalid SessionStatus: {0}")]
     InvalidSessionStatus(u8),
 
+    /// Invalid status for MO::Confirmation.
+    InvalidConfirmationStatus(u8),
+
+    /// Invalid level for MT::Priority, valid range is 1-5.
+    InvalidPriority(u16),
+
+    /// Not a valid IMEI
+    InvalidImei(String),
+
+    /// Combination of Information Elements not valid for a message
+    InvalidMessage(String),
+
+    /// Message not compliant with the specification, see [crate::Violation]
+    SpecViolation(Vec<crate::Violation>),
+
+    /// Protocol revision other than the supported version 1
+    UnsupportedVersion {
+        version: u8,
+        offset: usize,
+    },
+
+    /// Length field not valid for the Information Element
+    InvalidIELength {
+        iei: u8,
+        length: u16,
+        offset: usize,
+    },
+
+    /// Information Element ended before its declared length
+    TruncatedElement {
+        iei: u8,
+        offset: usize,
+    },
+
+    /// Information Element Identifier not known
+    UnknownIEI {
+        iei: u8,
+        offset: usize,
+    },
+
+    /// Overall message length doesn't match the sum of its elements
+    MessageLengthMismatch {
+        declared: usize,
+        actual: usize,
+    },
+
+    /// Output buffer can't hold the encoded message
+    BufferTooSmall {
+        required: usize,
+        available: usize,
+    },
+
+    /// Payload longer than accepted by the protocol or the modem, see
+    /// [crate::Modem]
+    PayloadOversized {
+        length: usize,
+        limit: usize,
+    },
+
+    /// Payload not carrying a valid fragment, see [crate::fragment]
+    InvalidFragment(String),
+
+    /// Time of session that can't be represented, in seconds since 1970
+    InvalidTimeOfSession(i64),
+
+    /// Coordinate out of the valid range, [-90, 90] for latitude and
+    /// [-180, 180] for longitude
+    InvalidCoordinate {
+        latitude: f64,
+        longitude: f64,
+    },
+
     /// Undefined error
-    #[error("Undefined error")]
     Undefined,
 }
 
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            Error::IO(e) => e.fmt(f),
+            Error::UnexpectedEof => write!(f, "Unexpected end of input"),
+            Error::UninitializedFieldError(e) => e.fmt(f),
+            Error::WrongIEType(name, expected, iei) => {
+                write!(
+                    f,
+                    "Wrong IEI for {name}. Expected {expected} instead of {iei}"
+                )
+            }
+            Error::InvalidMessageStatus(s) => write!(f, "Invalid MessageStatus: {s}"),
+            Error::InvalidSessionStatus(s) => write!(f, "Invalid SessionStatus: {s}"),
+            Error::InvalidConfirmationStatus(s) => {
+                write!(f, "Invalid MO Confirmation status: {s}")
+            }
+            Error::InvalidPriority(level) => write!(f, "Invalid MT Priority: {level}"),
+            Error::InvalidImei(reason) => write!(f, "Invalid IMEI: {reason}"),
+            Error::InvalidMessage(reason) => write!(f, "Invalid message: {reason}"),
+            Error::SpecViolation(violations) => {
+                write!(f, "Message violates the specification: ")?;
+                for (i, v) in violations.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{v}")?;
+                }
+                Ok(())
+            }
+            Error::UnsupportedVersion { version, offset } => {
+                write!(f, "Unsupported protocol version {version} at byte {offset}")
+            }
+            Error::InvalidIELength {
+                iei,
+                length,
+                offset,
+            } => write!(
+                f,
+                "Invalid length {length} for IEI 0x{iei:02x} at byte {offset}"
+            ),
+            Error::TruncatedElement { iei, offset } => {
+                write!(f, "Truncated IEI 0x{iei:02x} starting at byte {offset}")
+            }
+            Error::UnknownIEI { iei, offset } => {
+                write!(f, "Unknown IEI 0x{iei:02x} at byte {offset}")
+            }
+            Error::MessageLengthMismatch { declared, actual } => write!(
+                f,
+                "Declared message length {declared} but elements sum {actual} bytes"
+            ),
+            Error::BufferTooSmall {
+                required,
+                available,
+            } => write!(
+                f,
+                "Buffer of {available} bytes too small, {required} bytes required"
+            ),
+            Error::PayloadOversized { length, limit } => write!(
+                f,
+                "Payload of {length} bytes exceeds the limit of {limit} bytes"
+            ),
+            Error::InvalidFragment(reason) => write!(f, "Invalid fragment: {reason}"),
+            Error::InvalidTimeOfSession(t) => write!(f, "Invalid time of session: {t}"),
+            Error::InvalidCoordinate {
+                latitude,
+                longitude,
+            } => write!(f, "Invalid coordinate: {latitude}, {longitude}"),
+            Error::Undefined => write!(f, "Undefined error"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::IO(e) => e.source(),
+            Error::UninitializedFieldError(e) => e.source(),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        Error::IO(e)
+    }
+}
+
+impl From<derive_builder::UninitializedFieldError> for Error {
+    fn from(e: derive_builder::UninitializedFieldError) -> Self {
+        Error::UninitializedFieldError(e)
+    }
+}
+
+impl Error {
+    /// Shift the position of a positioned error by `base` bytes
+    ///
+    /// Elements report positions relative to their own first byte, i.e. the
+    /// IEI. The message parser shifts those to the position in the message.
+    pub(crate) fn shift(self, base: usize) -> Self {
+        match self {
+            Error::UnsupportedVersion { version, offset } => Error::UnsupportedVersion {
+                version,
+                offset: base + offset,
+            },
+            Error::InvalidIELength {
+                iei,
+                length,
+                offset,
+            } => Error::InvalidIELength {
+                iei,
+                length,
+                offset: base + offset,
+            },
+            Error::TruncatedElement { iei, offset } => Error::TruncatedElement {
+                iei,
+                offset: base + offset,
+            },
+            Error::UnknownIEI { iei, offset } => Error::UnknownIEI {
+                iei,
+                offset: base + offset,
+            },
+            e => e,
+        }
+    }
+
+    /// Unexpected end of input
+    pub(crate) fn eof() -> Self {
+        Error::UnexpectedEof
+    }
+
+    pub(crate) fn is_eof(&self) -> bool {
+        match self {
+            #[cfg(feature = "std")]
+            Error::IO(e) => e.kind() == std::io::ErrorKind::UnexpectedEof,
+            Error::UnexpectedEof => true,
+            _ => false,
+        }
+    }
+
+    /// Interpret an unexpected end of input as a truncated element
+    pub(crate) fn truncated(self, iei: u8) -> Self {
+        if self.is_eof() {
+            Error::TruncatedElement { iei, offset: 0 }
+        } else {
+            self
+        }
+    }
+}
+
 pub(crate) type Result<T> = core::result::Result<T, Error>;
@@ -0,0 +1,373 @@
+//! Fragmentation of data larger than a single payload
+//!
+//! This is an application-level convention, thus both ends must agree on
+//! it. The data is split in fragments, each one sent in its own message,
+//! with the payload prefixed by a 6-bytes header:
+//!
+//! * ID (2-bytes): Identifies the fragmented data, i.e. the set of fragments
+//! * Index (2-bytes): Position of the fragment, starting at 0
+//! * Count (2-bytes): Total number of fragments in the set
+//!
+//! All fields are big-endian, as in the Direct-IP protocol. MT messages are
+//! composed with [split], while fragments arriving on MO messages are
+//! reassembled with a [Reassembler].
+
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::error::{Error, Result};
+use crate::mo::MOMessage;
+use crate::mt::{MTMessage, MTMessageBuilder};
+use crate::{Imei, Modem};
+
+/// Size of the fragment header, in bytes
+pub const HEADER_LEN: usize = 6;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Header identifying a fragment within its set
+pub struct FragmentHeader {
+    pub id: u16,
+    pub index: u16,
+    pub count: u16,
+}
+
+impl FragmentHeader {
+    pub fn encode(&self) -> [u8; HEADER_LEN] {
+        let mut buf = [0u8; HEADER_LEN];
+        buf[0..2].copy_from_slice(&self.id.to_be_bytes());
+        buf[2..4].copy_from_slice(&self.index.to_be_bytes());
+        buf[4..6].copy_from_slice(&self.count.to_be_bytes());
+        buf
+    }
+
+    /// Split a payload into its fragment header and contents
+    pub fn decode(payload: &[u8]) -> Result<(FragmentHeader, &[u8])> {
+        if payload.len() < HEADER_LEN {
+            return Err(Error::InvalidFragment(format!(
+                "Payload of {} bytes is shorter than the header",
+                payload.len()
+            )));
+        }
+        let (header, contents) = payload.split_at(HEADER_LEN);
+        let header = FragmentHeader {
+            id: u16::from_be_bytes([header[0], header[1]]),
+            index: u16::from_be_bytes([header[2], header[3]]),
+            count: u16::from_be_bytes([header[4], header[5]]),
+        };
+        if header.index >= header.count {
+            return Err(Error::InvalidFragment(format!(
+                "Index {} out of a set of {} fragments",
+                header.index, header.count
+            )));
+        }
+        Ok((header, contents))
+    }
+}
+
+/// Split data into a sequence of MT messages
+///
+/// Each fragment is as large as the `modem` can receive. The messages are
+/// composed from the builders given by `template` for each fragment index,
+/// thus it should define at least the IMEI and the client message ID.
+pub fn split<F>(data: &[u8], id: u16, modem: Modem, mut template: F) -> Result<Vec<MTMessage>>
+where
+    F: FnMut(u16) -> MTMessageBuilder,
+{
+    let size = modem.max_mt_payload().saturating_sub(HEADER_LEN);
+    if size == 0 {
+        return Err(Error::InvalidFragment(
+            "Payload limit can't fit a fragment".to_string(),
+        ));
+    }
+
+    let mut chunks: Vec<&[u8]> = data.chunks(size).collect();
+    if chunks.is_empty() {
+        // Even empty data is sent, as a single fragment with no contents
+        chunks.push(&[]);
+    }
+    let count = u16::try_from(chunks.len()).map_err(|_| {
+        Error::InvalidFragment(format!("{} fragments, more than 65535", chunks.len()))
+    })?;
+
+    chunks
+        .into_iter()
+        .zip(0..count)
+        .map(|(chunk, index)| {
+            let mut payload = FragmentHeader { id, index, count }.encode().to_vec();
+            payload.extend_from_slice(chunk);
+            template(index).payload(payload).modem(modem).build()
+        })
+        .collect()
+}
+
+/// Fragments received so far for a set
+struct Pending {
+    count: u16,
+    started: DateTime<Utc>,
+    fragments: BTreeMap<u16, Vec<u8>>,
+}
+
+impl Pending {
+    fn missing(&self) -> Vec<u16> {
+        (0..self.count)
+            .filter(|i| !self.fragments.contains_key(i))
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A set of fragments that timed out before it was complete
+pub struct Incomplete {
+    pub imei: Imei,
+    pub id: u16,
+    pub missing: Vec<u16>,
+}
+
+/// Reassemble data from the fragments carried by MO messages
+///
+/// Fragments are grouped by IMEI and ID, and can arrive in any order.
+/// Retransmitted fragments simply replace the previous copy. Time is given
+/// by the Time of Session in the MO Header, so a set expires once its
+/// first fragment is older than the timeout.
+pub struct Reassembler {
+    timeout: Duration,
+    pending: BTreeMap<(Imei, u16), Pending>,
+}
+
+impl Reassembler {
+    pub fn new(timeout: Duration) -> Self {
+        Reassembler {
+            timeout,
+            pending: BTreeMap::new(),
+        }
+    }
+
+    /// Add the fragment carried by a message
+    ///
+    /// Returns the original data once all the fragments of its set were
+    /// received.
+    pub fn push(&mut self, msg: &MOMessage) -> Result<Option<Vec<u8>>> {
+        let header = msg
+            .header()
+            .ok_or_else(|| Error::InvalidMessage("Missing MO Header".to_string()))?;
+        let payload = msg
+            .payload()
+            .ok_or_else(|| Error::InvalidFragment("Missing MO Payload".to_string()))?;
+        let (fragment, contents) = FragmentHeader::decode(payload)?;
+
+        let key = (header.imei(), fragment.id);
+        let pending = self.pending.entry(key).or_insert_with(|| Pending {
+            count: fragment.count,
+            started: header.time_of_session(),
+            fragments: BTreeMap::new(),
+        });
+        if pending.count != fragment.count {
+            return Err(Error::InvalidFragment(format!(
+                "Set {} has {} fragments, not {}",
+                fragment.id, pending.count, fragment.count
+            )));
+        }
+        pending.fragments.insert(fragment.index, contents.to_vec());
+
+        if pending.fragments.len() < usize::from(pending.count) {
+            return Ok(None);
+        }
+        let data = self
+            .pending
+            .remove(&key)
+            .map(|p| p.fragments.into_values().flatten().collect());
+        Ok(data)
+    }
+
+    /// Indices of the fragments not yet received, if the set is pending
+    pub fn missing(&self, imei: Imei, id: u16) -> Option<Vec<u16>> {
+        self.pending.get(&(imei, id)).map(Pending::missing)
+    }
+
+    /// Number of incomplete sets
+    pub fn len(&self) -> usize {
+        self.pending.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Drop the sets that timed out by `now`, returning what they missed
+    pub fn expire(&mut self, now: DateTime<Utc>) -> Vec<Incomplete> {
+        let expired: Vec<_> = self
+            .pending
+            .iter()
+            .filter(|(_, p)| p.started + self.timeout < now)
+            .map(|(key, _)| *key)
+            .collect();
+        expired
+            .into_iter()
+            .filter_map(|key| {
+                self.pending.remove(&key).map(|p| Incomplete {
+                    imei: key.0,
+                    id: key.1,
+                    missing: p.missing(),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test_fragment {
+    use super::{split, FragmentHeader, Incomplete, Reassembler, HEADER_LEN};
+    use crate::mo::{MOMessage, SessionStatus};
+    use crate::mt::MTMessage;
+    use crate::{Error, Imei, Modem};
+    use chrono::{DateTime, Duration, Utc};
+
+    fn imei() -> Imei {
+        "300234010753376".parse().unwrap()
+    }
+
+    fn t0() -> DateTime<Utc> {
+        "2000-03-14T12:12:12Z".parse().unwrap()
+    }
+
+    /// MO message carrying a given payload, as if sent by the modem
+    fn mo(payload: &[u8], minutes: i64) -> MOMessage {
+        MOMessage::builder()
+            .cdr_uid(9999)
+            .imei(imei())
+            .session_status(SessionStatus::Success)
+            .momsn(1)
+            .mtmsn(0)
+            .time_of_session(t0() + Duration::minutes(minutes))
+            .payload(payload.to_vec())
+            .build()
+            .unwrap()
+    }
+
+    fn fragments(data: &[u8], modem: Modem) -> Vec<MTMessage> {
+        split(data, 42, modem, |i| {
+            MTMessage::builder()
+                .client_msg_id(u32::from(i) + 1)
+                .imei(imei())
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn header_roundtrip() {
+        let header = FragmentHeader {
+            id: 513,
+            index: 2,
+            count: 3,
+        };
+        let mut payload = header.encode().to_vec();
+        payload.push(0x42);
+        let (decoded, contents) = FragmentHeader::decode(&payload).unwrap();
+        assert_eq!(decoded, header);
+        assert_eq!(contents, [0x42]);
+    }
+
+    #[test]
+    fn header_invalid() {
+        assert!(matches!(
+            FragmentHeader::decode(&[0, 1, 0, 0]),
+            Err(Error::InvalidFragment(_))
+        ));
+        // Index beyond count
+        assert!(matches!(
+            FragmentHeader::decode(&[0, 1, 0, 3, 0, 3]),
+            Err(Error::InvalidFragment(_))
+        ));
+    }
+
+    #[test]
+    fn split_for_modem() {
+        let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        let msgs = fragments(&data, Modem::Iridium9603);
+        // 270 bytes payload, thus 264 bytes per fragment
+        assert_eq!(msgs.len(), 4);
+        for (i, msg) in msgs.iter().enumerate() {
+            let (header, _) = FragmentHeader::decode(msg.payload().unwrap()).unwrap();
+            assert_eq!(usize::from(header.index), i);
+            assert_eq!(header.count, 4);
+            assert_eq!(header.id, 42);
+            assert!(msg.payload().unwrap().len() <= 270);
+        }
+        assert_eq!(
+            msgs[3].payload().unwrap().len(),
+            HEADER_LEN + 1000 - 3 * 264
+        );
+    }
+
+    #[test]
+    fn split_empty() {
+        let msgs = fragments(&[], Modem::Iridium9603);
+        assert_eq!(msgs.len(), 1);
+        assert_eq!(msgs[0].payload().unwrap().len(), HEADER_LEN);
+    }
+
+    #[test]
+    fn reassemble_out_of_order() {
+        let data: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        let msgs = fragments(&data, Modem::Iridium9603);
+
+        let mut reassembler = Reassembler::new(Duration::minutes(30));
+        for i in [2, 0, 3, 0] {
+            let msg = mo(msgs[i].payload().unwrap(), 0);
+            assert_eq!(reassembler.push(&msg).unwrap(), None);
+        }
+        assert_eq!(reassembler.missing(imei(), 42), Some(vec![1]));
+
+        let msg = mo(msgs[1].payload().unwrap(), 0);
+        assert_eq!(reassembler.push(&msg).unwrap(), Some(data));
+        assert!(reassembler.is_empty());
+        assert_eq!(reassembler.missing(imei(), 42), None);
+    }
+
+    #[test]
+    fn reassemble_count_mismatch() {
+        let mut reassembler = Reassembler::new(Duration::minutes(30));
+        let first = FragmentHeader {
+            id: 7,
+            index: 0,
+            count: 3,
+        };
+        reassembler.push(&mo(&first.encode(), 0)).unwrap();
+        let other = FragmentHeader {
+            id: 7,
+            index: 1,
+            count: 2,
+        };
+        assert!(matches!(
+            reassembler.push(&mo(&other.encode(), 0)),
+            Err(Error::InvalidFragment(_))
+        ));
+    }
+
+    #[test]
+    fn expire() {
+        let msgs = fragments(&[0x42; 600], Modem::Iridium9603);
+        let mut reassembler = Reassembler::new(Duration::minutes(30));
+        reassembler
+            .push(&mo(msgs[1].payload().unwrap(), 0))
+            .unwrap();
+
+        assert!(reassembler.expire(t0() + Duration::minutes(30)).is_empty());
+        assert_eq!(reassembler.len(), 1);
+
+        let expired = reassembler.expire(t0() + Duration::minutes(31));
+        assert_eq!(
+            expired,
+            [Incomplete {
+                imei: imei(),
+                id: 42,
+                missing: vec![0, 2]
+            }]
+        );
+        assert!(reassembler.is_empty());
+    }
+}
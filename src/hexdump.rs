@@ -0,0 +1,112 @@
+//! Annotated hexdump of Messages
+//!
+//! Each byte range of a message is listed with its offset and a label of
+//! what it is, such as the protocol revision, the Information Element
+//! Identifiers and lengths, and each field of the elements, for instance:
+//!
+//! ```text
+//! 0000  01                                               Protocol revision
+//! 0001  00 1c                                            Message length: 28
+//! 0003  44                                               IEI: MT Confirmation
+//! 0004  00 19                                            IE length: 25
+//! 0006  00 00 04 57                                      Client message ID
+//! ```
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+use crate::InformationElement;
+
+/// Number of bytes shown per line
+const BYTES_PER_LINE: usize = 16;
+
+/// Builder of an annotated hexdump, consuming the encoded message in order
+pub(crate) struct HexDump {
+    bytes: Vec<u8>,
+    offset: usize,
+    out: String,
+}
+
+impl HexDump {
+    pub(crate) fn new(bytes: Vec<u8>) -> Self {
+        HexDump {
+            bytes,
+            offset: 0,
+            out: String::new(),
+        }
+    }
+
+    /// Label the next `len` bytes
+    ///
+    /// Long fields are wrapped, with the label on the first line only.
+    fn field(&mut self, len: usize, label: &str) {
+        let end = (self.offset + len).min(self.bytes.len());
+        let mut label = Some(label);
+        for start in (self.offset..end).step_by(BYTES_PER_LINE) {
+            let chunk = &self.bytes[start..(start + BYTES_PER_LINE).min(end)];
+            let hex: Vec<_> = chunk.iter().map(|b| format!("{:02x}", b)).collect();
+            let line = format!(
+                "{:04x}  {:<width$}  {}",
+                start,
+                hex.join(" "),
+                label.take().unwrap_or_default(),
+                width = BYTES_PER_LINE * 3 - 1
+            );
+            // Writing to a String never fails
+            let _ = writeln!(self.out, "{}", line.trim_end());
+        }
+        self.offset = end;
+    }
+
+    /// Protocol revision and overall message length
+    pub(crate) fn message_header(&mut self, len: u16) {
+        self.field(1, "Protocol revision");
+        self.field(2, &format!("Message length: {}", len));
+    }
+
+    /// Identifier, length, and each field of an Information Element
+    pub(crate) fn element<E: InformationElement>(&mut self, name: &str, element: &E) {
+        self.field(1, &format!("IEI: {}", name));
+        self.field(2, &format!("IE length: {}", element.len()));
+        for (label, len) in element.fields() {
+            self.field(len, label);
+        }
+    }
+
+    pub(crate) fn finish(self) -> String {
+        self.out
+    }
+}
+
+#[cfg(test)]
+mod test_hexdump {
+    use super::HexDump;
+
+    #[test]
+    fn field() {
+        let mut dump = HexDump::new(vec![0x01, 0x00, 0x1f]);
+        dump.message_header(31);
+        assert_eq!(
+            dump.finish(),
+            format!(
+                "0000  01{}Protocol revision\n0001  00 1f{}Message length: 31\n",
+                " ".repeat(47),
+                " ".repeat(44)
+            )
+        );
+    }
+
+    #[test]
+    fn wrap() {
+        let mut dump = HexDump::new((0..20).collect());
+        dump.field(20, "Payload");
+        let out = dump.finish();
+        let lines: Vec<_> = out.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("0000  00 01 02"));
+        assert!(lines[0].ends_with("0f  Payload"));
+        assert_eq!(lines[1], "0010  10 11 12 13");
+    }
+}
@@ -0,0 +1,333 @@
+//! International Mobile Equipment Identity
+//!
+//! The IMEI identifies the modem (SSD) exchanging messages with the Iridium
+//! Gateway. It is a 15-digit number transmitted in ASCII format, where the
+//! last digit is a Luhn check digit of the previous 14.
+
+use alloc::format;
+
+use core::fmt;
+use core::str::FromStr;
+
+use crate::error::{Error, Result};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+/// Equipment identifier of an SSD
+///
+/// Parsing from a string is strict, requiring 15 ASCII digits with a valid
+/// check digit. On the other hand, any 15 bytes decoded from a message are
+/// preserved as they are, so that a message can be re-encoded exactly. Use
+/// [Imei::is_valid] to verify those.
+///
+/// # Example
+///
+/// ```
+/// use directip::Imei;
+///
+/// let imei: Imei = "300234010753376".parse().unwrap();
+/// assert_eq!(imei.to_string(), "300234010753376");
+/// assert!("300234010753370".parse::<Imei>().is_err());
+/// ```
+pub struct Imei([u8; 15]);
+
+impl Imei {
+    /// The 15 bytes as transmitted
+    pub fn as_bytes(&self) -> &[u8; 15] {
+        &self.0
+    }
+
+    /// True if composed of ASCII digits only
+    fn is_numeric(&self) -> bool {
+        self.0.iter().all(|d| d.is_ascii_digit())
+    }
+
+    /// True if composed of 15 ASCII digits with a valid check digit
+    pub fn is_valid(&self) -> bool {
+        self.is_numeric() && luhn(&self.0)
+    }
+
+    /// Parse anything produced by Display, either 15 digits or 30
+    /// hexadecimal characters
+    ///
+    /// Unlike [FromStr], the check digit is not verified, so that any IMEI
+    /// decoded from a message can be restored.
+    ///
+    /// ```
+    /// use directip::Imei;
+    ///
+    /// let imei = Imei::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
+    /// assert_eq!(Imei::from_display(&imei.to_string()).unwrap(), imei);
+    /// ```
+    pub fn from_display(s: &str) -> Result<Imei> {
+        if s.len() == 15 && s.bytes().all(|b| b.is_ascii_digit()) {
+            return Imei::try_from(s.as_bytes());
+        }
+        if s.len() == 30 {
+            let mut bytes = [0u8; 15];
+            for (b, hex) in bytes.iter_mut().zip(s.as_bytes().chunks(2)) {
+                *b = core::str::from_utf8(hex)
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                    .ok_or_else(|| Error::InvalidImei(format!("{s} is not hexadecimal")))?;
+            }
+            return Ok(Imei(bytes));
+        }
+        Err(Error::InvalidImei(format!(
+            "{s} is neither 15 digits nor 30 hexadecimal characters"
+        )))
+    }
+}
+
+/// Luhn checksum of a sequence of ASCII digits, including the check digit
+fn luhn(digits: &[u8]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .map(|d| u32::from(d - b'0'))
+        .enumerate()
+        .map(|(i, d)| match (i % 2, d * 2) {
+            (0, _) => d,
+            (_, dd) if dd > 9 => dd - 9,
+            (_, dd) => dd,
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+impl From<[u8; 15]> for Imei {
+    fn from(bytes: [u8; 15]) -> Self {
+        Imei(bytes)
+    }
+}
+
+impl From<Imei> for [u8; 15] {
+    fn from(imei: Imei) -> Self {
+        imei.0
+    }
+}
+
+impl TryFrom<&[u8]> for Imei {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self> {
+        bytes
+            .try_into()
+            .map(Imei)
+            .map_err(|_| Error::InvalidImei(format!("{} bytes instead of 15", bytes.len())))
+    }
+}
+
+impl PartialEq<[u8; 15]> for Imei {
+    fn eq(&self, other: &[u8; 15]) -> bool {
+        &self.0 == other
+    }
+}
+
+impl FromStr for Imei {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let imei = Imei::try_from(s.as_bytes())
+            .map_err(|_| Error::InvalidImei(format!("{s} is not 15 digits long")))?;
+        if !imei.is_numeric() {
+            return Err(Error::InvalidImei(format!("{s} is not composed of digits")));
+        }
+        if !luhn(imei.as_bytes()) {
+            return Err(Error::InvalidImei(format!(
+                "{s} has an invalid check digit"
+            )));
+        }
+        Ok(imei)
+    }
+}
+
+/// The 15 digits, or 30 hexadecimal characters if it is not numeric
+impl fmt::Display for Imei {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_numeric() {
+            // Safe, since all bytes are ASCII digits
+            f.write_str(core::str::from_utf8(&self.0).unwrap())
+        } else {
+            self.0.iter().try_for_each(|b| write!(f, "{:02x}", b))
+        }
+    }
+}
+
+impl fmt::Debug for Imei {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Imei(\"{}\")", self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Imei {
+    fn serialize<S: serde::Serializer>(
+        &self,
+        serializer: S,
+    ) -> core::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Accepts anything produced by Display, thus without verifying the check
+/// digit, so that any decoded message can be restored. Human-readable
+/// formats also accept an array of 15 integers, as previously serialized.
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Imei {
+    fn deserialize<D: serde::Deserializer<'de>>(
+        deserializer: D,
+    ) -> core::result::Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(ImeiVisitor)
+        } else {
+            deserializer.deserialize_str(ImeiVisitor)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+struct ImeiVisitor;
+
+#[cfg(feature = "serde")]
+impl<'de> serde::de::Visitor<'de> for ImeiVisitor {
+    type Value = Imei;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an IMEI as 15 digits, 30 hexadecimal digits, or 15 bytes")
+    }
+
+    fn visit_str<E: serde::de::Error>(self, s: &str) -> core::result::Result<Imei, E> {
+        Imei::from_display(s).map_err(E::custom)
+    }
+
+    fn visit_seq<A: serde::de::SeqAccess<'de>>(
+        self,
+        mut seq: A,
+    ) -> core::result::Result<Imei, A::Error> {
+        use serde::de::Error as _;
+
+        let mut bytes = [0u8; 15];
+        for (i, b) in bytes.iter_mut().enumerate() {
+            *b = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(i, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(A::Error::invalid_length(16, &self));
+        }
+        Ok(Imei(bytes))
+    }
+}
+
+#[cfg(test)]
+mod test_imei {
+    use super::{Error, Imei};
+
+    #[test]
+    fn parse() {
+        let imei: Imei = "300234010753376".parse().unwrap();
+        assert_eq!(imei.as_bytes(), b"300234010753376");
+        assert!(imei.is_valid());
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for s in ["30023401075337", "3002340107533760", "30023401075337a", ""] {
+            assert!(matches!(s.parse::<Imei>(), Err(Error::InvalidImei(_))));
+        }
+    }
+
+    #[test]
+    fn parse_check_digit() {
+        assert!("012345678901237".parse::<Imei>().is_ok());
+        for d in (0..=9).filter(|d| *d != 7) {
+            let s = format!("01234567890123{d}");
+            assert!(matches!(s.parse::<Imei>(), Err(Error::InvalidImei(_))));
+        }
+    }
+
+    #[test]
+    fn display() {
+        let imei: Imei = "300234010753376".parse().unwrap();
+        assert_eq!(imei.to_string(), "300234010753376");
+
+        // Not a valid IMEI, but preserved as decoded
+        let imei = Imei::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
+        assert!(!imei.is_valid());
+        assert_eq!(imei.to_string(), "000102030405060708090a0b0c0d0e");
+    }
+
+    #[test]
+    fn from_display() {
+        // Check digit is not verified
+        let imei = Imei::from_display("300234010753370").unwrap();
+        assert_eq!(imei.as_bytes(), b"300234010753370");
+        assert!(!imei.is_valid());
+
+        let imei = Imei::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]);
+        assert_eq!(Imei::from_display(&imei.to_string()).unwrap(), imei);
+
+        for s in [
+            "30023401075337",
+            "000102030405060708090a0b0c0d0g",
+            "é".repeat(15).as_str(),
+        ] {
+            assert!(matches!(Imei::from_display(s), Err(Error::InvalidImei(_))));
+        }
+    }
+
+    #[test]
+    fn from_bytes() {
+        let bytes = *b"300234010753376";
+        let imei = Imei::from(bytes);
+        assert_eq!(imei, bytes);
+        assert_eq!(<[u8; 15]>::from(imei), bytes);
+        assert!(Imei::try_from(&bytes[..14]).is_err());
+    }
+
+    #[test]
+    fn ord() {
+        let a: Imei = "012345678901237".parse().unwrap();
+        let b: Imei = "300234010753376".parse().unwrap();
+        assert!(a < b);
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test_imei_serde {
+    use super::Imei;
+
+    #[test]
+    fn serialize() {
+        let imei: Imei = "300234010753376".parse().unwrap();
+        assert_eq!(serde_json::to_string(&imei).unwrap(), "\"300234010753376\"");
+    }
+
+    #[test]
+    fn roundtrip() {
+        for imei in [
+            "300234010753376".parse().unwrap(),
+            Imei::from([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]),
+        ] {
+            let json = serde_json::to_string(&imei).unwrap();
+            let roundtrip: Imei = serde_json::from_str(&json).unwrap();
+            assert_eq!(imei, roundtrip);
+        }
+    }
+
+    #[test]
+    fn deserialize_invalid() {
+        assert!(serde_json::from_str::<Imei>("\"30023401075337\"").is_err());
+        assert!(serde_json::from_str::<Imei>("42").is_err());
+        assert!(serde_json::from_str::<Imei>("[0, 1, 2]").is_err());
+        assert!(serde_json::from_str::<Imei>("[0,1,2,3,4,5,6,7,8,9,10,11,12,13,14,15]").is_err());
+    }
+
+    #[test]
+    // As serialized before IMEIs were strings
+    fn deserialize_legacy_array() {
+        let imei: Imei =
+            serde_json::from_str("[51,48,48,50,51,52,48,49,48,55,53,51,51,55,54]").unwrap();
+        assert_eq!(imei, "300234010753376".parse::<Imei>().unwrap());
+    }
+}
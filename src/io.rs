@@ -0,0 +1,161 @@
+//! Minimal I/O used to parse and encode Information Elements
+//!
+//! With the `std` feature these are the `std::io` traits together with the
+//! byteorder extensions, so any reader or writer can be used. Without it,
+//! equivalent traits are implemented for slices and vectors only, which is
+//! all that the slice-based API needs.
+
+#[cfg(feature = "std")]
+pub(crate) use byteorder::{ReadBytesExt, WriteBytesExt};
+#[cfg(feature = "std")]
+pub(crate) use std::io::{Read, Write};
+
+#[cfg(not(feature = "std"))]
+pub(crate) use self::core_io::{Read, ReadBytesExt, Write, WriteBytesExt};
+
+#[cfg(not(feature = "std"))]
+mod core_io {
+    use alloc::vec::Vec;
+
+    use byteorder::ByteOrder;
+
+    use crate::error::{Error, Result};
+
+    pub(crate) trait Read {
+        /// Read up to the size of the buffer, returning the number of bytes
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+        fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<()> {
+            while !buf.is_empty() {
+                match self.read(buf)? {
+                    0 => return Err(Error::UnexpectedEof),
+                    n => buf = &mut buf[n..],
+                }
+            }
+            Ok(())
+        }
+
+        fn chain<R: Read>(self, next: R) -> Chain<Self, R>
+        where
+            Self: Sized,
+        {
+            Chain {
+                first: self,
+                second: next,
+                done_first: false,
+            }
+        }
+    }
+
+    impl Read for &[u8] {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            let n = buf.len().min(self.len());
+            let (a, b) = self.split_at(n);
+            buf[..n].copy_from_slice(a);
+            *self = b;
+            Ok(n)
+        }
+    }
+
+    impl<R: Read + ?Sized> Read for &mut R {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            (**self).read(buf)
+        }
+    }
+
+    /// Two readers, one after the other
+    pub(crate) struct Chain<T, U> {
+        first: T,
+        second: U,
+        done_first: bool,
+    }
+
+    impl<T: Read, U: Read> Read for Chain<T, U> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+            if !self.done_first {
+                match self.first.read(buf)? {
+                    0 if !buf.is_empty() => self.done_first = true,
+                    n => return Ok(n),
+                }
+            }
+            self.second.read(buf)
+        }
+    }
+
+    pub(crate) trait ReadBytesExt: Read {
+        fn read_u8(&mut self) -> Result<u8> {
+            let mut buf = [0; 1];
+            self.read_exact(&mut buf)?;
+            Ok(buf[0])
+        }
+
+        fn read_u16<B: ByteOrder>(&mut self) -> Result<u16> {
+            let mut buf = [0; 2];
+            self.read_exact(&mut buf)?;
+            Ok(B::read_u16(&buf))
+        }
+
+        fn read_i16<B: ByteOrder>(&mut self) -> Result<i16> {
+            let mut buf = [0; 2];
+            self.read_exact(&mut buf)?;
+            Ok(B::read_i16(&buf))
+        }
+
+        fn read_u32<B: ByteOrder>(&mut self) -> Result<u32> {
+            let mut buf = [0; 4];
+            self.read_exact(&mut buf)?;
+            Ok(B::read_u32(&buf))
+        }
+    }
+
+    impl<R: Read + ?Sized> ReadBytesExt for R {}
+
+    pub(crate) trait Write {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()>;
+    }
+
+    impl Write for Vec<u8> {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+            self.extend_from_slice(buf);
+            Ok(())
+        }
+    }
+
+    impl Write for &mut [u8] {
+        fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+            if buf.len() > self.len() {
+                return Err(Error::UnexpectedEof);
+            }
+            let (a, b) = core::mem::take(self).split_at_mut(buf.len());
+            a.copy_from_slice(buf);
+            *self = b;
+            Ok(())
+        }
+    }
+
+    pub(crate) trait WriteBytesExt: Write {
+        fn write_u8(&mut self, n: u8) -> Result<()> {
+            self.write_all(&[n])
+        }
+
+        fn write_u16<B: ByteOrder>(&mut self, n: u16) -> Result<()> {
+            let mut buf = [0; 2];
+            B::write_u16(&mut buf, n);
+            self.write_all(&buf)
+        }
+
+        fn write_i16<B: ByteOrder>(&mut self, n: i16) -> Result<()> {
+            let mut buf = [0; 2];
+            B::write_i16(&mut buf, n);
+            self.write_all(&buf)
+        }
+
+        fn write_u32<B: ByteOrder>(&mut self, n: u32) -> Result<()> {
+            let mut buf = [0; 4];
+            B::write_u32(&mut buf, n);
+            self.write_all(&buf)
+        }
+    }
+
+    impl<W: Write + ?Sized> WriteBytesExt for W {}
+}
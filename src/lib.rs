@@ -1,13 +1,56 @@
 //! Direct-IP Protocol
 //!
+//! # Features
+//!
+//! * `std` (default): Parse from any [std::io::Read] and iterate over
+//!   concatenated messages with `MessageReader`. Without it, the crate is
+//!   `no_std` and only requires `alloc`, parsing and encoding with slices of
+//!   bytes, such as [Message::from_bytes] and [Message::encode].
+//! * `serde`: Serialize and deserialize messages. In human-readable formats,
+//!   such as JSON, IMEIs are strings of digits and payloads are hexadecimal
+//!   strings. See the `encoding` module.
+//! * `serde-base64`: Add `encoding::base64`, to serialize bytes as base64
+//!   strings with `#[serde(with)]`. Payloads remain hexadecimal.
+//! * `tokio`: A codec to frame messages on asynchronous streams.
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 
+extern crate alloc;
+
+mod borrowed;
+#[cfg(feature = "tokio")]
+pub mod codec;
+mod describe;
+mod diff;
+#[cfg(feature = "serde")]
+pub mod encoding;
 mod error;
+pub mod fragment;
+mod hexdump;
+mod imei;
+mod io;
 pub mod mo;
+mod modem;
 pub mod mt;
+#[cfg(feature = "std")]
+mod reader;
+pub mod sequence;
+mod validation;
 
-use std::io::{Seek, SeekFrom};
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
 
-use crate::error::{Error, Result};
+pub use crate::error::Error;
+use crate::error::Result;
+use crate::io::{Read, Write};
+pub use borrowed::{ElementRef, Elements, MOHeaderRef, MTHeaderRef, MessageRef};
+pub use diff::Difference;
+pub use imei::Imei;
+pub use modem::Modem;
+#[cfg(feature = "std")]
+pub use reader::MessageReader;
+pub use validation::Violation;
 
 trait InformationElement {
     fn identifier(&self) -> u8;
@@ -20,24 +63,70 @@ trait InformationElement {
         3 + usize::from(self.len())
     }
 
-    fn write<W: std::io::Write>(&self, wtr: &mut W) -> Result<usize>;
+    fn write<W: Write>(&self, wtr: &mut W) -> Result<usize>;
 
+    /// Layout of the contents as labelled fields, with their size in bytes
+    ///
+    /// This excludes the identifier and the field len, and is used to
+    /// annotate a hexdump. By default, the contents are a single field.
+    fn fields(&self) -> Vec<(&'static str, usize)> {
+        vec![("Contents", usize::from(self.len()))]
+    }
+
+    /// Export Information Element to a vec, failing if it can't be encoded
+    fn try_to_vec(&self) -> Result<Vec<u8>> {
+        let mut buffer: Vec<u8> = Vec::new();
+        self.write(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    #[allow(dead_code)]
     /// Export Information Element to a vec
     fn to_vec(&self) -> Vec<u8> {
-        let mut buffer: Vec<u8> = Vec::new();
-        self.write(&mut buffer)
-            .expect("Failed to write Information Element to a vec.");
-        buffer
+        self.try_to_vec()
+            .expect("Failed to write Information Element to a vec.")
+    }
+}
+
+/// Direction of a Message
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Direction {
+    MO,
+    MT,
+}
+
+/// Identify the direction of a Message from an Information Element Identifier
+///
+/// MO elements are identified in the range 0x01-0x0F while MT elements are
+/// in the range 0x41-0x4F, including elements still unknown by this library.
+fn direction(iei: u8) -> Option<Direction> {
+    match iei {
+        0x01..=0x0f => Some(Direction::MO),
+        0x41..=0x4f => Some(Direction::MT),
+        _ => None,
     }
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Message {
     MO(mo::MOMessage),
     MT(mt::MTMessage),
 }
 
+/// Human-readable report of the Message
+///
+/// Each Information Element is listed with its identifier, length, and
+/// decoded fields.
+impl core::fmt::Display for Message {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Message::MO(m) => m.fmt(f),
+            Message::MT(m) => m.fmt(f),
+        }
+    }
+}
+
 // consider enum_dispatch
 impl Message {
     /// Identify the type of a Message between MO or MT
@@ -48,37 +137,153 @@ impl Message {
         }
     }
 
+    /// Parse a Message from a slice of bytes
+    ///
+    /// The direction, MO or MT, is identified by the first Information
+    /// Element.
+    pub fn from_bytes(buffer: &[u8]) -> Result<Self> {
+        Message::read(buffer)
+    }
+
     /// Parse a Message from a reader
-    pub fn from_reader<R: std::io::Read + Seek>(mut rdr: R) -> Result<Self> {
-        match mt::MTMessage::from_reader(&mut rdr) {
-            Ok(msg) => Ok(Message::MT(msg)),
-            Err(Error::WrongIEType(_, _, _)) => {
-                rdr.seek(SeekFrom::Start(0))?;
-                let msg = mo::MOMessage::from_reader(rdr)?;
-                Ok(Message::MO(msg))
-            }
-            Err(e) => Err(e),
+    ///
+    /// The direction, MO or MT, is identified by the first Information
+    /// Element.
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: std::io::Read>(rdr: R) -> Result<Self> {
+        Message::read(rdr)
+    }
+
+    fn read<R: Read>(mut rdr: R) -> Result<Self> {
+        // Protocol version, message length, and the first IEI
+        let mut buffer = [0u8; 4];
+        rdr.read_exact(&mut buffer)?;
+        let rdr = buffer.chain(rdr);
+        match direction(buffer[3]) {
+            Some(Direction::MO) => Ok(Message::MO(mo::MOMessage::read(rdr)?)),
+            Some(Direction::MT) => Ok(Message::MT(mt::MTMessage::read(rdr)?)),
+            None => Err(Error::UnknownIEI {
+                iei: buffer[3],
+                offset: 3,
+            }),
         }
     }
 
     /// Extract the IMEI from a Message
-    pub fn imei(&self) -> Option<[u8; 15]> {
+    pub fn imei(&self) -> Option<Imei> {
         match &self {
             Message::MO(m) => m.imei(),
             Message::MT(m) => m.imei(),
         }
     }
 
+    /// Parse a Message from a slice of bytes, rejecting any spec violation
+    ///
+    /// Unlike [Message::from_bytes], which tolerates duplicate or missing
+    /// elements, this fails with [Error::SpecViolation] listing everything
+    /// found by [Message::validate].
+    pub fn from_bytes_strict(buffer: &[u8]) -> Result<Self> {
+        Message::from_bytes(buffer)?.strict()
+    }
+
+    /// Parse a Message from a reader, rejecting any spec violation
+    ///
+    /// Unlike [Message::from_reader], which tolerates duplicate or missing
+    /// elements, this fails with [Error::SpecViolation] listing everything
+    /// found by [Message::validate].
+    #[cfg(feature = "std")]
+    pub fn from_reader_strict<R: std::io::Read>(rdr: R) -> Result<Self> {
+        Message::from_reader(rdr)?.strict()
+    }
+
+    fn strict(self) -> Result<Self> {
+        let violations = self.validate();
+        if violations.is_empty() {
+            Ok(self)
+        } else {
+            Err(Error::SpecViolation(violations))
+        }
+    }
+
+    /// Verify this Message against the Direct-IP specification
+    ///
+    /// Returns all violations found, thus an empty list for a compliant
+    /// message.
+    pub fn validate(&self) -> Vec<Violation> {
+        let buffer = match self.try_to_vec() {
+            Ok(buffer) => buffer,
+            Err(Error::PayloadOversized { length, .. }) => {
+                let iei = match self {
+                    Message::MO(_) => 0x02,
+                    Message::MT(_) => 0x42,
+                };
+                return vec![Violation::InvalidLength {
+                    iei,
+                    length: length.try_into().unwrap_or(u16::MAX),
+                }];
+            }
+            Err(Error::InvalidTimeOfSession(t)) => return vec![Violation::InvalidTimeOfSession(t)],
+            Err(_) => return vec![Violation::InvalidFraming],
+        };
+        match MessageRef::from_bytes(&buffer) {
+            Ok(msg) => msg.validate(),
+            // Without any Information Element, there is no Header either
+            Err(_) if buffer.len() == 3 => vec![Violation::MissingHeader],
+            Err(_) => vec![Violation::InvalidFraming],
+        }
+    }
+
     pub fn to_vec(&self) -> Vec<u8> {
         match &self {
             Message::MO(m) => m.to_vec(),
             Message::MT(m) => m.to_vec(),
         }
     }
+
+    /// Encode into a vector, failing if anything can't be encoded
+    pub(crate) fn try_to_vec(&self) -> Result<Vec<u8>> {
+        match &self {
+            Message::MO(m) => m.try_to_vec(),
+            Message::MT(m) => m.try_to_vec(),
+        }
+    }
+
+    /// Annotated hexdump of the encoded message
+    ///
+    /// Each byte range is labelled with what it is, such as the protocol
+    /// revision, the message length, and each field of the Information
+    /// Elements. Fails if the message can't be encoded.
+    pub fn hexdump(&self) -> Result<String> {
+        match &self {
+            Message::MO(m) => m.hexdump(),
+            Message::MT(m) => m.hexdump(),
+        }
+    }
+
+    /// Field-level differences with another Message
+    ///
+    /// Information Elements are matched by identifier and their fields are
+    /// compared as encoded. Identical messages have no differences. Fails
+    /// if either message can't be encoded.
+    pub fn diff(&self, other: &Message) -> Result<Vec<Difference>> {
+        let fields = |msg: &Message| match msg {
+            Message::MO(m) => m.element_fields(),
+            Message::MT(m) => m.element_fields(),
+        };
+        Ok(diff::diff(fields(self)?, fields(other)?))
+    }
+
+    /// Encode into a slice of bytes, returning the number of bytes written
+    pub fn encode(&self, buffer: &mut [u8]) -> Result<usize> {
+        match &self {
+            Message::MO(m) => m.encode(buffer),
+            Message::MT(m) => m.encode(buffer),
+        }
+    }
 }
 
 pub fn sample() -> Message {
-    let msg = mt::MTMessage::from_reader(
+    let msg = mt::MTMessage::from_bytes(
         [
             0x01, 0x00, 0x1c, 0x44, 0x00, 0x19, 0x00, 0x00, 0x27, 0x0f, 0x00, 0x01, 0x02, 0x03,
             0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0xff, 0xff, 0xff,
@@ -88,3 +293,228 @@ pub fn sample() -> Message {
     );
     Message::MT(msg.unwrap())
 }
+
+#[cfg(test)]
+mod test_message {
+    #[cfg(feature = "std")]
+    use super::Violation;
+    use super::{sample, Error, Message};
+    #[cfg(feature = "std")]
+    use std::io::Cursor;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn from_reader_mt() {
+        let msg = Message::from_reader(Cursor::new(sample().to_vec())).unwrap();
+        assert_eq!(msg.message_type(), "MT");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn from_reader_unknown_direction() {
+        let buffer = [0x01, 0x00, 0x04, 0x20, 0x00, 0x01, 0x01];
+        assert!(matches!(
+            Message::from_reader(Cursor::new(buffer)),
+            Err(Error::UnknownIEI {
+                iei: 0x20,
+                offset: 3
+            })
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn from_reader_mo() {
+        let buffer = [0x01, 0x00, 0x04, 0x05, 0x00, 0x01, 0x01];
+        let msg = Message::from_reader(Cursor::new(buffer)).unwrap();
+        assert_eq!(msg.message_type(), "MO");
+    }
+
+    #[test]
+    fn validate() {
+        assert!(sample().validate().is_empty());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn from_reader_strict() {
+        // Two MO Confirmations
+        let buffer = [
+            0x01, 0x00, 0x08, 0x05, 0x00, 0x01, 0x01, 0x05, 0x00, 0x01, 0x01,
+        ];
+        let msg = Message::from_reader(Cursor::new(buffer)).unwrap();
+        assert_eq!(msg.validate(), [Violation::DuplicateElement { iei: 0x05 }]);
+        assert!(matches!(
+            Message::from_reader_strict(Cursor::new(buffer)),
+            Err(Error::SpecViolation(v)) if v == [Violation::DuplicateElement { iei: 0x05 }]
+        ));
+
+        assert!(Message::from_reader_strict(Cursor::new(sample().to_vec())).is_ok());
+    }
+
+    #[test]
+    fn from_bytes() {
+        let msg = Message::from_bytes(&sample().to_vec()).unwrap();
+        assert_eq!(msg.message_type(), "MT");
+        assert!(matches!(
+            Message::from_bytes(&sample().to_vec()[..20]),
+            Err(Error::TruncatedElement { iei: 0x44, .. })
+        ));
+    }
+
+    #[test]
+    fn encode() {
+        let expected = sample().to_vec();
+        let mut buffer = [0u8; 64];
+        let n = sample().encode(&mut buffer).unwrap();
+        assert_eq!(&buffer[..n], expected);
+
+        assert!(matches!(
+            sample().encode(&mut buffer[..30]),
+            Err(Error::BufferTooSmall {
+                required: 31,
+                available: 30
+            })
+        ));
+    }
+}
+
+#[cfg(test)]
+mod test_message_traits {
+    use super::{sample, Message};
+    use std::collections::HashSet;
+
+    #[test]
+    fn clone_eq() {
+        let msg = sample();
+        assert_eq!(msg.clone(), msg);
+
+        let other = Message::from_bytes(&{
+            let mut buffer = msg.to_vec();
+            // Last byte of the message status
+            *buffer.last_mut().unwrap() = 0xf4;
+            buffer
+        })
+        .unwrap();
+        assert_ne!(other, msg);
+    }
+
+    #[test]
+    fn hash() {
+        let mut set = HashSet::new();
+        set.insert(sample());
+        set.insert(sample());
+        assert_eq!(set.len(), 1);
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test_message_serde {
+    use super::{mo::MOMessage, mo::SessionStatus, Message};
+    use chrono::{DateTime, Utc};
+
+    fn message() -> Message {
+        let msg = MOMessage::builder()
+            .cdr_uid(9999)
+            .imei("300234010753376".parse().unwrap())
+            .session_status(SessionStatus::Success)
+            .momsn(999)
+            .mtmsn(111)
+            .time_of_session("2000-03-14T12:12:12Z".parse::<DateTime<Utc>>().unwrap())
+            .payload(b"Hello".to_vec())
+            .location(15.5, -38.25, 10)
+            .build()
+            .unwrap();
+        Message::MO(msg)
+    }
+
+    #[test]
+    fn json_human_readable() {
+        let json = serde_json::to_string(&message()).unwrap();
+        assert!(json.contains(r#""imei":"300234010753376""#));
+        assert!(json.contains(r#""payload":"48656c6c6f""#));
+    }
+
+    #[test]
+    fn json_roundtrip() {
+        let msg = message();
+        let json = serde_json::to_string(&msg).unwrap();
+        let roundtrip: Message = serde_json::from_str(&json).unwrap();
+        assert_eq!(roundtrip.to_vec(), msg.to_vec());
+    }
+
+    #[test]
+    // Element contents can't be longer than its 2-bytes length allows
+    fn json_oversized_element() {
+        let bytes = "00".repeat(70_000);
+        let json = format!(
+            r#"{{"MO":{{"elements":[{{"Unknown":{{"iei":69,"bytes":"{}"}}}}]}}}}"#,
+            bytes
+        );
+        let err = serde_json::from_str::<Message>(&json).unwrap_err();
+        assert!(err.to_string().contains("at most 65535 bytes"));
+
+        let json = json.replace("00", "");
+        assert!(serde_json::from_str::<Message>(&json).is_ok());
+    }
+
+    #[test]
+    // A time of session before 1970 is accepted, but can't be encoded
+    fn json_before_1970() {
+        let json = serde_json::to_string(&message()).unwrap();
+        let json = json.replace("2000-03-14T12:12:12Z", "1960-01-01T00:00:00Z");
+        let msg: Message = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            msg.validate(),
+            [super::Violation::InvalidTimeOfSession(-315_619_200)]
+        );
+        assert!(msg.encode(&mut [0; 128]).is_err());
+    }
+
+    #[test]
+    // Payloads can't be longer than accepted by the protocol
+    fn json_oversized_payload() {
+        let mt = crate::mt::MTMessage::builder()
+            .client_msg_id(1)
+            .imei("300234010753376".parse().unwrap())
+            .payload(b"Hello".to_vec())
+            .build()
+            .unwrap();
+        for (msg, limit) in [(message(), 1960), (Message::MT(mt), 1890)] {
+            let json = serde_json::to_string(&msg).unwrap();
+
+            let valid = json.replace("48656c6c6f", &"00".repeat(limit));
+            assert!(serde_json::from_str::<Message>(&valid).is_ok());
+
+            let oversized = json.replace("48656c6c6f", &"00".repeat(limit + 1));
+            let err = serde_json::from_str::<Message>(&oversized).unwrap_err();
+            assert!(err
+                .to_string()
+                .contains(&format!("at most {} bytes", limit)));
+        }
+    }
+
+    #[test]
+    // The elements can't add up to more than the 2-bytes overall length
+    fn json_oversized_message() {
+        let bytes = "00".repeat(40_000);
+        let element = format!(r#"{{"Unknown":{{"iei":69,"bytes":"{}"}}}}"#, bytes);
+        let json = format!(r#"{{"MO":{{"elements":[{},{}]}}}}"#, element, element);
+        let err = serde_json::from_str::<Message>(&json).unwrap_err();
+        assert!(err.to_string().contains("at most 65535 bytes"));
+    }
+
+    #[test]
+    fn json_empty_message() {
+        let msg: Message = serde_json::from_str(r#"{"MO":{"elements":[]}}"#).unwrap();
+        assert_eq!(msg.validate(), [super::Violation::MissingHeader]);
+    }
+
+    #[test]
+    fn json_roundtrip_mt() {
+        let msg = super::sample();
+        let json = serde_json::to_string(&msg).unwrap();
+        let roundtrip: Message = serde_json::from_str(&json).unwrap();
+        assert_eq!(roundtrip.to_vec(), msg.to_vec());
+    }
+}
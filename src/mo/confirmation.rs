@@ -0,0 +1,186 @@
+//! Mobile Originated Confirmation
+//!
+//! A mobile originated confirmation is the information element used by the
+//! receiving server to acknowledge an MO message delivered by the Iridium
+//! Gateway. It is defined by an information element identifier (IEI) with
+//! value 0x05.
+
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use byteorder::BigEndian;
+use derive_builder::Builder;
+use log::debug;
+
+use crate::error::{Error, Result};
+use crate::io::{Read, ReadBytesExt, Write, WriteBytesExt};
+use crate::InformationElement;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Hash)]
+#[builder(pattern = "owned", build_fn(error = "crate::error::Error"))]
+/// Mobile Originated Confirmation
+///
+/// IEI: 0x05
+///
+/// Fixed total size of 4 bytes.
+///
+/// # Components
+///
+/// * Status: A single byte, 1 if the MO message was successfully received,
+///   or 0 otherwise.
+pub struct Confirmation {
+    successful: bool,
+}
+
+impl InformationElement for Confirmation {
+    /// Information Element Identifier
+    fn identifier(&self) -> u8 {
+        0x05
+    }
+
+    // Length field of the Confirmation element
+    //
+    // This is a fixed value, the status byte only.
+    fn len(&self) -> u16 {
+        1
+    }
+
+    fn fields(&self) -> Vec<(&'static str, usize)> {
+        vec![("Status", 1)]
+    }
+
+    fn write<W: Write>(&self, wtr: &mut W) -> Result<usize> {
+        wtr.write_u8(0x05)?;
+        wtr.write_u16::<BigEndian>(self.len())?;
+        wtr.write_u8(u8::from(self.successful))?;
+        Ok(4)
+    }
+}
+
+impl Confirmation {
+    /// Parse a Confirmation from a Read trait
+    pub(super) fn from_reader<R: Read>(mut rdr: R) -> Result<Confirmation> {
+        let iei = rdr.read_u8()?;
+        if iei != 0x05 {
+            debug!(
+                "Wrong IEI type for MO-Confirmation. Expected 0x05 instead of {}",
+                &iei
+            );
+            return Err(Error::WrongIEType("MO-Confirmation".to_string(), 0x05, iei));
+        }
+        let len = rdr.read_u16::<BigEndian>()?;
+        if len != 1 {
+            debug!("MO-Confirmation with unexpected length: {} bytes", len);
+            return Err(Error::InvalidIELength {
+                iei,
+                length: len,
+                offset: 1,
+            });
+        }
+        let successful = match rdr.read_u8()? {
+            0 => false,
+            1 => true,
+            s => return Err(Error::InvalidConfirmationStatus(s)),
+        };
+        Ok(Confirmation { successful })
+    }
+
+    /// True if the MO message was successfully received
+    pub fn is_successful(&self) -> bool {
+        self.successful
+    }
+
+    pub fn builder() -> ConfirmationBuilder {
+        ConfirmationBuilder::default()
+    }
+}
+
+impl core::fmt::Display for Confirmation {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        if self.successful {
+            write!(f, "Status: successful")
+        } else {
+            write!(f, "Status: failed")
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_mo_confirmation {
+    use super::{Confirmation, Error, InformationElement};
+
+    #[test]
+    fn write() {
+        let confirmation = Confirmation { successful: true };
+        assert_eq!(confirmation.to_vec(), [0x05, 0x00, 0x01, 0x01]);
+
+        let confirmation = Confirmation { successful: false };
+        assert_eq!(confirmation.to_vec(), [0x05, 0x00, 0x01, 0x00]);
+    }
+
+    #[test]
+    fn read() {
+        let confirmation = Confirmation::from_reader([0x05, 0x00, 0x01, 0x01].as_slice()).unwrap();
+        assert!(confirmation.is_successful());
+    }
+
+    #[test]
+    fn read_invalid_status() {
+        let confirmation = Confirmation::from_reader([0x05, 0x00, 0x01, 0x02].as_slice());
+        assert!(matches!(
+            confirmation,
+            Err(Error::InvalidConfirmationStatus(0x02))
+        ));
+    }
+
+    #[test]
+    fn roundtrip_write_n_read() {
+        for successful in [true, false] {
+            let confirmation = Confirmation { successful };
+            assert_eq!(
+                confirmation,
+                Confirmation::from_reader(confirmation.to_vec().as_slice()).unwrap()
+            );
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test_confirmation_serde {
+    use super::Confirmation;
+
+    #[test]
+    fn roundtrip() {
+        let confirmation = Confirmation { successful: true };
+        let json = serde_json::to_string(&confirmation).unwrap();
+
+        let roundtrip: Confirmation = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(confirmation, roundtrip);
+    }
+}
+
+#[cfg(test)]
+mod test_mo_confirmation_builder {
+    use super::{ConfirmationBuilder, Error};
+
+    #[test]
+    fn build_missing_required() {
+        let confirmation = ConfirmationBuilder::default().build();
+        assert!(matches!(
+            confirmation,
+            Err(Error::UninitializedFieldError(_))
+        ))
+    }
+
+    #[test]
+    fn build() {
+        let confirmation = ConfirmationBuilder::default()
+            .successful(true)
+            .build()
+            .unwrap();
+        assert!(confirmation.is_successful());
+    }
+}
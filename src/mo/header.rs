@@ -4,21 +4,26 @@
 //! compose a mobile originated message. It is defined by an information
 //! element identifier (IEI) with value 0x01.
 
-use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use byteorder::BigEndian;
 use chrono::{DateTime, TimeZone, Utc};
 use derive_builder::Builder;
 
 use crate::error::Error;
-use crate::InformationElement;
+use crate::io::{Read, ReadBytesExt, Write, WriteBytesExt};
+use crate::{Imei, InformationElement};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 /// Session Status
 ///
-/// Status:
-/// * 0: Success
+/// Status of the SBD session between the modem and the Iridium Gateway.
+/// The Display implementation describes each status.
 ///
-pub(crate) enum SessionStatus {
+pub enum SessionStatus {
     Success,
     MTTooLarge,
     BadLocation,
@@ -35,7 +40,7 @@ impl SessionStatus {
     /// * Success: 0
     /// * MTTooLarge: 1
     ///
-    fn decode(status: &u8) -> Result<SessionStatus, Error> {
+    pub(crate) fn decode(status: &u8) -> Result<SessionStatus, Error> {
         match status {
             0 => Ok(SessionStatus::Success),
             1 => Ok(SessionStatus::MTTooLarge),
@@ -50,7 +55,7 @@ impl SessionStatus {
     }
 
     /// Parse a SessionStatus from a Read trait
-    fn from_reader<R: std::io::Read>(mut rdr: R) -> Result<Self, Error> {
+    fn from_reader<R: Read>(mut rdr: R) -> Result<Self, Error> {
         let status = rdr.read_u8()?;
         SessionStatus::decode(&status)
     }
@@ -70,14 +75,14 @@ impl SessionStatus {
     }
 
     /// Save a SessionStatus using a Write trait
-    fn write<W: std::io::Write>(&self, wtr: &mut W) -> Result<usize, Error> {
+    fn write<W: Write>(&self, wtr: &mut W) -> Result<usize, Error> {
         wtr.write_u8(self.encode())?;
         Ok(1)
     }
 }
 
-impl std::fmt::Display for SessionStatus {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Display for SessionStatus {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         match self {
             SessionStatus::Success => write!(f, "Session completed successfully"),
             SessionStatus::MTTooLarge => {
@@ -95,6 +100,26 @@ impl std::fmt::Display for SessionStatus {
     }
 }
 
+impl core::fmt::Display for Header {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        writeln!(f, "CDR reference: {}", self.cdr_uid)?;
+        writeln!(f, "IMEI: {}", self.imei)?;
+        writeln!(
+            f,
+            "Session status: {:?} ({})",
+            self.session_status, self.session_status
+        )?;
+        writeln!(f, "MOMSN: {}", self.momsn)?;
+        writeln!(f, "MTMSN: {}", self.mtmsn)?;
+        write!(
+            f,
+            "Time of session: {}",
+            self.time_of_session
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, true)
+        )
+    }
+}
+
 #[cfg(test)]
 mod test_session_status {
     use super::Error;
@@ -187,8 +212,11 @@ mod test_session_status_serde {
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Builder, Debug, PartialEq)]
-#[builder(pattern = "owned", build_fn(error = "crate::error::Error"))]
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Hash)]
+#[builder(
+    pattern = "owned",
+    build_fn(error = "crate::error::Error", validate = "Self::validate")
+)]
 /// Mobile Originated Header
 ///
 /// IEI: 0x01
@@ -205,9 +233,10 @@ mod test_session_status_serde {
 /// * MOMSN
 /// * MTMSN
 /// * Time of Session
-pub(crate) struct Header {
+pub struct Header {
     cdr_uid: u32,
-    imei: [u8; 15],
+    #[builder(setter(into))]
+    imei: Imei,
     session_status: SessionStatus,
     momsn: u16,
     mtmsn: u16,
@@ -216,23 +245,35 @@ pub(crate) struct Header {
 
 impl Header {
     // Import a Header from a Read trait
-    pub(super) fn from_reader<R: std::io::Read>(mut rdr: R) -> Result<Header, Error> {
+    pub(super) fn from_reader<R: Read>(mut rdr: R) -> Result<Header, Error> {
         let iei = rdr.read_u8()?;
-        assert_eq!(iei, 0x01);
+        if iei != 0x01 {
+            return Err(Error::WrongIEType("MO-Header".to_string(), 0x01, iei));
+        }
         let len = rdr.read_u16::<BigEndian>()?;
-        assert_eq!(len, 28);
+        if len != 28 {
+            return Err(Error::InvalidIELength {
+                iei,
+                length: len,
+                offset: 1,
+            });
+        }
 
         let cdr_uid = rdr.read_u32::<BigEndian>()?;
 
         let mut imei = [0; 15];
         rdr.read_exact(&mut imei)?;
+        let imei = Imei::from(imei);
 
         let session_status = SessionStatus::from_reader(&mut rdr)?;
         let momsn = rdr.read_u16::<BigEndian>()?;
         let mtmsn = rdr.read_u16::<BigEndian>()?;
 
         let dt = rdr.read_u32::<BigEndian>()?;
-        let time_of_session = Utc.timestamp_opt(dt.into(), 0).single().unwrap();
+        let time_of_session = Utc
+            .timestamp_opt(dt.into(), 0)
+            .single()
+            .ok_or(Error::InvalidTimeOfSession(dt.into()))?;
 
         Ok(Header {
             cdr_uid,
@@ -244,14 +285,58 @@ impl Header {
         })
     }
 
-    pub(crate) fn imei(&self) -> [u8; 15] {
+    /// CDR Reference, a unique ID for each call data record
+    pub fn cdr_uid(&self) -> u32 {
+        self.cdr_uid
+    }
+
+    /// IMEI of the MO message origin
+    pub fn imei(&self) -> Imei {
         self.imei
     }
 
+    /// Status of the SBD session
+    pub fn session_status(&self) -> &SessionStatus {
+        &self.session_status
+    }
+
+    /// Mobile Originated Message Sequence Number
+    pub fn momsn(&self) -> u16 {
+        self.momsn
+    }
+
+    /// Mobile Terminated Message Sequence Number
+    pub fn mtmsn(&self) -> u16 {
+        self.mtmsn
+    }
+
+    /// Time of the SBD session
+    pub fn time_of_session(&self) -> DateTime<Utc> {
+        self.time_of_session
+    }
+
     #[allow(dead_code)]
     pub(crate) fn builder() -> HeaderBuilder {
         HeaderBuilder::default()
     }
+
+    /// Time of session as encoded, in seconds since 1970
+    ///
+    /// Fails for any time before 1970 or after 2106, which don't fit in the
+    /// 4 bytes of the field.
+    fn encoded_time_of_session(time: &DateTime<Utc>) -> Result<u32, Error> {
+        let t = time.timestamp();
+        u32::try_from(t).map_err(|_| Error::InvalidTimeOfSession(t))
+    }
+}
+
+impl HeaderBuilder {
+    fn validate(&self) -> Result<(), Error> {
+        if let Some(ref time_of_session) = self.time_of_session {
+            Header::encoded_time_of_session(time_of_session)?;
+        }
+        Ok(())
+    }
 }
 
 //#[allow(dead_code)]
@@ -269,22 +354,29 @@ impl InformationElement for Header {
         28
     }
 
+    /// Fields of a Header, to annotate a hexdump
+    fn fields(&self) -> Vec<(&'static str, usize)> {
+        vec![
+            ("CDR reference", 4),
+            ("IMEI", 15),
+            ("Session status", 1),
+            ("MOMSN", 2),
+            ("MTMSN", 2),
+            ("Time of session", 4),
+        ]
+    }
+
     /// Export a Header using a Write trait
-    fn write<W: std::io::Write>(&self, wtr: &mut W) -> Result<usize, Error> {
+    fn write<W: Write>(&self, wtr: &mut W) -> Result<usize, Error> {
         wtr.write_u8(0x01)?;
         wtr.write_u16::<BigEndian>(28)?;
         wtr.write_u32::<BigEndian>(self.cdr_uid)?;
-        wtr.write_all(&self.imei)?;
+        wtr.write_all(self.imei.as_bytes())?;
         let n = self.session_status.write(wtr)?;
         debug_assert_eq!(n, 1);
         wtr.write_u16::<BigEndian>(self.momsn)?;
         wtr.write_u16::<BigEndian>(self.mtmsn)?;
-        wtr.write_u32::<BigEndian>(
-            self.time_of_session
-                .timestamp()
-                .try_into()
-                .expect("Can't handle time before 1970"),
-        )?;
+        wtr.write_u32::<BigEndian>(Header::encoded_time_of_session(&self.time_of_session)?)?;
         Ok(28)
     }
 }
@@ -297,7 +389,7 @@ mod test_mt_header {
     fn header_write() {
         let header = Header {
             cdr_uid: 9999,
-            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14],
+            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14].into(),
             session_status: SessionStatus::Success,
             momsn: 999,
             mtmsn: 111,
@@ -314,11 +406,28 @@ mod test_mt_header {
         );
     }
 
+    #[test]
+    // Time of session doesn't fit in the 4 bytes field
+    fn header_write_before_1970() {
+        let header = Header {
+            cdr_uid: 9999,
+            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14].into(),
+            session_status: SessionStatus::Success,
+            momsn: 999,
+            mtmsn: 111,
+            time_of_session: "1960-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap(),
+        };
+        assert!(matches!(
+            header.try_to_vec(),
+            Err(crate::error::Error::InvalidTimeOfSession(-315_619_200))
+        ));
+    }
+
     #[test]
     fn roundtrip_to_vec_n_read() {
         let header = Header {
             cdr_uid: 9999,
-            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14],
+            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14].into(),
             session_status: SessionStatus::Success,
             momsn: 999,
             mtmsn: 111,
@@ -340,7 +449,7 @@ mod test_header_serde {
     fn roundtrip() {
         let header = Header {
             cdr_uid: 9999,
-            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14],
+            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14].into(),
             session_status: SessionStatus::Success,
             momsn: 999,
             mtmsn: 111,
@@ -357,7 +466,7 @@ mod test_header_serde {
 
 #[cfg(test)]
 mod test_header_builder {
-    use super::{Error, HeaderBuilder, SessionStatus, Utc};
+    use super::{DateTime, Error, HeaderBuilder, SessionStatus, Utc};
 
     #[test]
     fn build_missing_required() {
@@ -373,9 +482,25 @@ mod test_header_builder {
             .session_status(SessionStatus::Success)
             .momsn(999)
             .mtmsn(9999)
-            .time_of_session(Utc::now())
+            .time_of_session("2000-03-14T12:12:12Z".parse::<DateTime<Utc>>().unwrap())
             .build()
             .unwrap();
         assert_eq!(9999, header.cdr_uid);
     }
+
+    #[test]
+    fn build_before_1970() {
+        let header = HeaderBuilder::default()
+            .cdr_uid(9999)
+            .imei([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 1, 2, 3, 4])
+            .session_status(SessionStatus::Success)
+            .momsn(999)
+            .mtmsn(9999)
+            .time_of_session("1960-01-01T00:00:00Z".parse::<DateTime<Utc>>().unwrap())
+            .build();
+        assert!(matches!(
+            header,
+            Err(Error::InvalidTimeOfSession(-315_619_200))
+        ));
+    }
 }
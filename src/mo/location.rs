@@ -1,10 +1,21 @@
-use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+//! Mobile Originated Location Information
+//!
+//! The MO location information is one of the information element types that
+//! compose a mobile originated message. It is defined by an information
+//! element identifier (IEI) with value 0x03, and carries the location of the
+//! modem estimated by the Iridium Gateway.
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use byteorder::BigEndian;
+use log::debug;
 
 use crate::error::{Error, Result};
+use crate::io::{Read, ReadBytesExt, Write, WriteBytesExt};
 use crate::InformationElement;
 
-#[allow(dead_code)]
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 enum Orientation {
     NE,
     NW,
@@ -13,7 +24,6 @@ enum Orientation {
 }
 
 impl Orientation {
-    #[allow(dead_code)]
     /// Decode an Orientation struct from a byte
     ///
     /// Only the two least significant bytes are used. The rest was supposed
@@ -29,7 +39,6 @@ impl Orientation {
         }
     }
 
-    #[allow(dead_code)]
     fn encode(&self) -> u8 {
         match self {
             Orientation::NE => 0,
@@ -52,125 +61,449 @@ mod test_orientation {
     }
 }
 
-#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, PartialEq)]
+/// Thousandths of minute in a degree, the resolution of the encoding
+const RESOLUTION: f64 = 60e3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 /// A geolocation coordinate
 ///
-/// A coordinate structure keep tight the encode and decode so avoiding bugs
-/// due to the wrong order as well as possible sizes.
+/// Latitude and longitude are kept as encoded by the protocol, in
+/// thousandths of minute, thus a Coordinate encodes and decodes back to
+/// exactly the same value. Decimal degrees are rounded to the nearest
+/// thousandth of minute, i.e. about 1.85 m of latitude.
+///
+/// ```
+/// use directip::mo::Coordinate;
 ///
-/// Keep in mind the valid range [-90:90] for latitude and [-180:180] for
-/// longitude.
-struct Coordinate {
-    latitude: f64,
-    longitude: f64,
+/// let coordinate = Coordinate::new(-33.5, 151.25).unwrap();
+/// assert_eq!(coordinate.to_string(), "33°30'00.000\"S 151°15'00.000\"E");
+/// assert_eq!(Coordinate::decode(&coordinate.encode()).unwrap(), coordinate);
+/// ```
+pub struct Coordinate {
+    latitude: i32,
+    longitude: i32,
 }
 
 impl Coordinate {
-    #[allow(dead_code)]
-    fn encode(&self) -> [u8; 7] {
-        let mut buf = [0u8; 7];
+    /// Latitude range, in thousandths of minute
+    const MAX_LATITUDE: i32 = 90 * 60_000;
+    /// Longitude range, in thousandths of minute
+    const MAX_LONGITUDE: i32 = 180 * 60_000;
+
+    /// Layout of an encoded Coordinate
+    pub(crate) const FIELDS: [(&'static str, usize); 5] = [
+        ("Orientation", 1),
+        ("Latitude degrees", 1),
+        ("Latitude thousandths of minute", 2),
+        ("Longitude degrees", 1),
+        ("Longitude thousandths of minute", 2),
+    ];
 
-        let orientation = if (self.latitude < 0.0) & (self.longitude > 0.0) {
-            Orientation::SE
-        } else if (self.latitude < 0.0) & (self.longitude < 0.0) {
-            Orientation::SW
-        } else if (self.latitude > 0.0) & (self.longitude < 0.0) {
-            Orientation::NW
+    /// Coordinate from decimal degrees
+    ///
+    /// Valid range is [-90, 90] for latitude and [-180, 180] for longitude.
+    pub fn new(latitude: f64, longitude: f64) -> Result<Coordinate> {
+        // NaN is not contained by any range
+        if !((-90.0..=90.0).contains(&latitude) && (-180.0..=180.0).contains(&longitude)) {
+            return Err(Error::InvalidCoordinate {
+                latitude,
+                longitude,
+            });
+        }
+        Ok(Coordinate {
+            latitude: Coordinate::round(latitude),
+            longitude: Coordinate::round(longitude),
+        })
+    }
+
+    /// Round decimal degrees to the nearest thousandth of minute
+    fn round(angle: f64) -> i32 {
+        // Half away from zero, since f64::round() requires std
+        let total = angle * RESOLUTION;
+        if total < 0.0 {
+            (total - 0.5) as i32
         } else {
-            Orientation::NE
+            (total + 0.5) as i32
+        }
+    }
+
+    /// Latitude in decimal degrees
+    pub fn latitude(&self) -> f64 {
+        f64::from(self.latitude) / RESOLUTION
+    }
+
+    /// Longitude in decimal degrees
+    pub fn longitude(&self) -> f64 {
+        f64::from(self.longitude) / RESOLUTION
+    }
+
+    /// Latitude in degrees, minutes and seconds
+    pub fn latitude_dms(&self) -> Dms {
+        Dms::new(self.latitude, 'N', 'S')
+    }
+
+    /// Longitude in degrees, minutes and seconds
+    pub fn longitude_dms(&self) -> Dms {
+        Dms::new(self.longitude, 'E', 'W')
+    }
+
+    #[cfg(feature = "serde")]
+    /// GeoJSON Point geometry of this coordinate
+    pub fn to_geojson(&self) -> GeoJsonPoint {
+        GeoJsonPoint {
+            coordinates: [self.longitude(), self.latitude()],
+        }
+    }
+
+    /// Split an angle into degrees and thousandths of minute
+    fn split(angle: i32) -> (u8, [u8; 2]) {
+        let angle = angle.unsigned_abs();
+        // Within the valid range, degrees fit in a byte
+        (
+            (angle / 60_000) as u8,
+            ((angle % 60_000) as u16).to_be_bytes(),
+        )
+    }
+
+    /// Encode as the 7 bytes used by the Location Information elements
+    pub fn encode(&self) -> [u8; 7] {
+        let mut buf = [0u8; 7];
+
+        // Zero is north, or east, as the sign is all that matters
+        let orientation = match (self.latitude < 0, self.longitude < 0) {
+            (false, false) => Orientation::NE,
+            (false, true) => Orientation::NW,
+            (true, false) => Orientation::SE,
+            (true, true) => Orientation::SW,
         };
         buf[0] = orientation.encode();
 
-        // It can't be larger than 90, so it is safe convert to u8
-        buf[1] = self.latitude.abs().trunc() as u8;
-        let tmp = ((self.latitude.abs().fract() * 60e3) as u16).to_be_bytes();
-        buf[2] = tmp[0];
-        buf[3] = tmp[1];
-
-        // It can't be larger than 180, so it is safe convert to u8
-        buf[4] = self.longitude.abs().trunc() as u8;
-        let tmp = ((self.longitude.abs().fract() * 60e3) as u16).to_be_bytes();
-        buf[5] = tmp[0];
-        buf[6] = tmp[1];
+        let (degrees, minutes) = Coordinate::split(self.latitude);
+        buf[1] = degrees;
+        buf[2..4].copy_from_slice(&minutes);
+
+        let (degrees, minutes) = Coordinate::split(self.longitude);
+        buf[4] = degrees;
+        buf[5..7].copy_from_slice(&minutes);
         buf
     }
 
-    #[allow(dead_code)]
-    fn decode(buffer: &[u8]) -> Coordinate {
-        if buffer.len() < 7 {
-            todo!()
-        }
-        let orientation = Orientation::decode(&buffer[0]).unwrap();
-        let lat_sign = match orientation {
-            Orientation::SE => -1.,
-            Orientation::SW => -1.,
-            _ => 1.,
-        };
-        let lon_sign = match orientation {
-            Orientation::NW => -1.,
-            Orientation::SW => -1.,
-            _ => 1.,
+    /// Decode from the 7 bytes without verifying the range
+    ///
+    /// Any byte sequence is accepted, thus a Location received from a
+    /// misbehaving gateway can still be inspected.
+    pub(crate) fn decode_unchecked(buffer: &[u8; 7]) -> Coordinate {
+        let (lat_sign, lon_sign) = match Orientation::decode(&buffer[0]) {
+            Ok(Orientation::NE) => (1, 1),
+            Ok(Orientation::NW) => (1, -1),
+            Ok(Orientation::SE) => (-1, 1),
+            Ok(Orientation::SW) => (-1, -1),
+            // All values are covered by the mask
+            Err(_) => unreachable!(),
         };
-
-        let lat_decimal = ((buffer[2] as u16) << 8 | buffer[3] as u16) as f64 / 60e3;
-        let latitude = lat_sign * (buffer[1] as f64 + lat_decimal);
-        let lon_decimal = ((buffer[5] as u16) << 8 | buffer[6] as u16) as f64 / 60e3;
-        let longitude = lon_sign * (buffer[4] as f64 + lon_decimal);
+        let lat_minutes = u16::from_be_bytes([buffer[2], buffer[3]]);
+        let lon_minutes = u16::from_be_bytes([buffer[5], buffer[6]]);
 
         Coordinate {
-            latitude,
-            longitude,
+            latitude: lat_sign * (i32::from(buffer[1]) * 60_000 + i32::from(lat_minutes)),
+            longitude: lon_sign * (i32::from(buffer[4]) * 60_000 + i32::from(lon_minutes)),
+        }
+    }
+
+    /// Decode from the 7 bytes used by the Location Information elements
+    ///
+    /// Fails if out of the valid range, or if any thousandths of minute are
+    /// 60000 or more, since those wouldn't encode back to the same bytes.
+    pub fn decode(buffer: &[u8; 7]) -> Result<Coordinate> {
+        let coordinate = Coordinate::decode_unchecked(buffer);
+        let lat_minutes = u16::from_be_bytes([buffer[2], buffer[3]]);
+        let lon_minutes = u16::from_be_bytes([buffer[5], buffer[6]]);
+        if lat_minutes >= 60_000
+            || lon_minutes >= 60_000
+            || coordinate.latitude.abs() > Coordinate::MAX_LATITUDE
+            || coordinate.longitude.abs() > Coordinate::MAX_LONGITUDE
+        {
+            debug!("Invalid coordinate: {:02x?}", buffer);
+            return Err(Error::InvalidCoordinate {
+                latitude: coordinate.latitude(),
+                longitude: coordinate.longitude(),
+            });
+        }
+        Ok(coordinate)
+    }
+}
+
+impl core::fmt::Display for Coordinate {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{} {}", self.latitude_dms(), self.longitude_dms())
+    }
+}
+
+/// Serialized in decimal degrees, as `{"latitude": .., "longitude": ..}`
+#[cfg(feature = "serde")]
+impl serde::Serialize for Coordinate {
+    fn serialize<S>(&self, serializer: S) -> core::result::Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("Coordinate", 2)?;
+        state.serialize_field("latitude", &self.latitude())?;
+        state.serialize_field("longitude", &self.longitude())?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Coordinate {
+    fn deserialize<D>(deserializer: D) -> core::result::Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        #[derive(serde::Deserialize)]
+        struct Degrees {
+            latitude: f64,
+            longitude: f64,
+        }
+
+        let degrees = Degrees::deserialize(deserializer)?;
+        Coordinate::new(degrees.latitude, degrees.longitude).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// An angle in degrees, minutes and seconds
+///
+/// Seconds are a multiple of 0.06, the thousandth of minute used by the
+/// encoding.
+pub struct Dms {
+    pub degrees: u8,
+    pub minutes: u8,
+    pub seconds: f64,
+    /// N or S for latitude, E or W for longitude
+    pub hemisphere: char,
+}
+
+impl Dms {
+    fn new(angle: i32, positive: char, negative: char) -> Dms {
+        let (degrees, minutes) = Coordinate::split(angle);
+        let minutes = u16::from_be_bytes(minutes);
+        Dms {
+            degrees,
+            minutes: (minutes / 1000) as u8,
+            seconds: f64::from(minutes % 1000) * 0.06,
+            hemisphere: if angle < 0 { negative } else { positive },
         }
     }
 }
 
+impl core::fmt::Display for Dms {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "{}°{:02}'{:06.3}\"{}",
+            self.degrees, self.minutes, self.seconds, self.hemisphere
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", rename = "Point")]
+/// A GeoJSON Point geometry, as defined by RFC 7946
+///
+/// Serialized as `{"type": "Point", "coordinates": [longitude, latitude]}`,
+/// thus longitude first.
+pub struct GeoJsonPoint {
+    pub coordinates: [f64; 2],
+}
+
 #[cfg(test)]
 mod test_coordinate {
     use super::Coordinate;
+    use crate::error::Error;
 
     #[test]
     fn encode() {
-        let c = Coordinate {
-            latitude: 15.0,
-            longitude: -38.0,
-        };
+        let c = Coordinate::new(15.0, -38.0).unwrap();
         let buf = [0x01, 0x0f, 0x00, 0x00, 0x26, 0x00, 0x00];
         assert_eq!(buf, c.encode())
     }
+
     #[test]
     fn roundtrip_decode_encode() {
         let buf = [0x01, 0x0f, 0x00, 0x00, 0x26, 0x00, 0x00];
-        assert_eq!(buf, Coordinate::decode(&buf).encode())
+        assert_eq!(buf, Coordinate::decode(&buf).unwrap().encode())
+    }
+
+    #[test]
+    // A zero latitude or longitude doesn't hide the sign of the other one
+    fn orientation_at_zero() {
+        let cases = [
+            (0.0, -38.0, 0x01),
+            (-15.0, 0.0, 0x02),
+            (0.0, 38.0, 0x00),
+            (15.0, 0.0, 0x00),
+            (0.0, 0.0, 0x00),
+        ];
+        for (latitude, longitude, orientation) in cases {
+            let c = Coordinate::new(latitude, longitude).unwrap();
+            assert_eq!(c.encode()[0], orientation);
+            let decoded = Coordinate::decode(&c.encode()).unwrap();
+            assert_eq!(decoded.latitude(), latitude);
+            assert_eq!(decoded.longitude(), longitude);
+        }
+    }
+
+    #[test]
+    fn roundtrip_resolution() {
+        for (latitude, longitude) in [
+            (33.171_85, -118.541_966),
+            (-0.000_008, 0.000_009),
+            (-89.999_999, 179.999_99),
+            (12.345_678_9, -98.765_432_1),
+        ] {
+            let c = Coordinate::new(latitude, longitude).unwrap();
+            // Within half a thousandth of minute
+            assert!((c.latitude() - latitude).abs() <= 0.5 / 60e3);
+            assert!((c.longitude() - longitude).abs() <= 0.5 / 60e3);
+            let decoded = Coordinate::decode(&c.encode()).unwrap();
+            assert_eq!(decoded, c);
+            assert_eq!(Coordinate::new(c.latitude(), c.longitude()).unwrap(), c);
+        }
+    }
+
+    #[test]
+    // Rounding up to a whole minute carries into the degrees
+    fn round_carry() {
+        let c = Coordinate::new(10.0 - 0.1 / 60e3, -(20.0 - 0.1 / 60e3)).unwrap();
+        assert_eq!(c.encode(), [0x01, 0x0a, 0x00, 0x00, 0x14, 0x00, 0x00]);
+    }
+
+    #[test]
+    fn invalid() {
+        for (latitude, longitude) in [
+            (90.1, 0.0),
+            (-90.1, 0.0),
+            (0.0, 180.1),
+            (0.0, -180.1),
+            (f64::NAN, 0.0),
+            (0.0, f64::INFINITY),
+        ] {
+            assert!(matches!(
+                Coordinate::new(latitude, longitude),
+                Err(Error::InvalidCoordinate { .. })
+            ));
+        }
+        assert!(Coordinate::new(-90.0, 180.0).is_ok());
+    }
+
+    #[test]
+    fn decode_invalid() {
+        // 91 degrees of latitude
+        let buf = [0x00, 0x5b, 0x00, 0x00, 0x00, 0x00, 0x00];
+        assert!(Coordinate::decode(&buf).is_err());
+        assert_eq!(Coordinate::decode_unchecked(&buf).latitude(), 91.0);
+        // 60 minutes
+        let buf = [0x00, 0x0a, 0xea, 0x60, 0x00, 0x00, 0x00];
+        assert!(Coordinate::decode(&buf).is_err());
+        assert_eq!(Coordinate::decode_unchecked(&buf).latitude(), 11.0);
+    }
+
+    #[test]
+    fn dms() {
+        let c = Coordinate::new(33.171_85, -118.541_966).unwrap();
+        let latitude = c.latitude_dms();
+        assert_eq!((latitude.degrees, latitude.minutes), (33, 10));
+        assert!((latitude.seconds - 18.66).abs() < 1e-9);
+        assert_eq!(latitude.hemisphere, 'N');
+        assert_eq!(c.longitude_dms().hemisphere, 'W');
+        assert_eq!(c.to_string(), "33°10'18.660\"N 118°32'31.080\"W");
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test_coordinate_serde {
+    use super::{Coordinate, GeoJsonPoint};
+
+    #[test]
+    fn geojson() {
+        let c = Coordinate::new(-12.5, 145.25).unwrap();
+        let json = serde_json::to_value(c.to_geojson()).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({"type": "Point", "coordinates": [145.25, -12.5]})
+        );
+        let point: GeoJsonPoint = serde_json::from_value(json).unwrap();
+        assert_eq!(point, c.to_geojson());
+    }
+
+    #[test]
+    fn degrees() {
+        let c = Coordinate::new(-12.5, 145.25).unwrap();
+        let json = serde_json::to_string(&c).unwrap();
+        assert_eq!(json, r#"{"latitude":-12.5,"longitude":145.25}"#);
+        assert_eq!(serde_json::from_str::<Coordinate>(&json).unwrap(), c);
+
+        assert!(serde_json::from_str::<Coordinate>(r#"{"latitude":91,"longitude":0}"#).is_err());
     }
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, PartialEq)]
-pub(super) struct Location {
-    coordinate: Coordinate,
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// Mobile Originated Location Information
+///
+/// IEI: 0x03
+///
+/// Fixed total size of 14 bytes.
+///
+/// # Components
+///
+/// * Coordinate: Latitude and longitude estimated by the Iridium Gateway,
+///   encoded in degrees and thousandths of minutes.
+/// * CEP radius: Circular Error Probable radius, in km, of the estimated
+///   location.
+///
+/// The coordinate is kept as received, thus it encodes back to the same
+/// bytes even if out of the valid range. See [Location::coordinate].
+pub struct Location {
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::coordinate"))]
+    coordinate: [u8; 7],
     cep_radius: u32,
 }
 
 impl Location {
-    #[allow(dead_code)]
-    fn decode(buffer: &[u8]) -> Result<Self> {
-        if buffer.len() < 3 {
-            todo!()
+    pub(super) fn new(coordinate: Coordinate, cep_radius: u32) -> Location {
+        Location {
+            coordinate: coordinate.encode(),
+            cep_radius,
         }
+    }
 
-        if buffer[0] != 0x03 {
-            todo!()
+    /// Parse a Location from a Read trait
+    pub(super) fn from_reader<R: Read>(mut rdr: R) -> Result<Location> {
+        let iei = rdr.read_u8()?;
+        if iei != 0x03 {
+            debug!(
+                "Wrong IEI type for MO-Location. Expected 0x03 instead of {}",
+                &iei
+            );
+            return Err(Error::WrongIEType("MO-Location".to_string(), 0x03, iei));
         }
-        let length: u16 = (buffer[1] as u16) << 8 | buffer[2] as u16;
-        if buffer.len() < length as usize + 3 {
-            todo!()
+        let len = rdr.read_u16::<BigEndian>()?;
+        if len != 11 {
+            debug!("MO-Location with unexpected length: {} bytes", len);
+            return Err(Error::InvalidIELength {
+                iei,
+                length: len,
+                offset: 1,
+            });
         }
-        let coordinate = Coordinate::decode(&buffer[3..]);
-        let cep_radius: u32 = (buffer[10] as u32) << 24
-            | (buffer[11] as u32) << 16
-            | (buffer[12] as u32) << 8
-            | buffer[13] as u32;
+
+        let mut coordinate = [0u8; 7];
+        rdr.read_exact(&mut coordinate)?;
+        let cep_radius = rdr.read_u32::<BigEndian>()?;
 
         Ok(Location {
             coordinate,
@@ -178,10 +511,27 @@ impl Location {
         })
     }
 
-    fn encode(&self) -> [u8; 7] {
-        todo!();
-        let buffer = [0u8; 7];
-        buffer
+    /// Estimated coordinate
+    ///
+    /// Fails if the coordinate received is not valid, see
+    /// [Coordinate::decode].
+    pub fn coordinate(&self) -> Result<Coordinate> {
+        Coordinate::decode(&self.coordinate)
+    }
+
+    /// Latitude in decimal degrees, as received even if not valid
+    pub fn latitude(&self) -> f64 {
+        Coordinate::decode_unchecked(&self.coordinate).latitude()
+    }
+
+    /// Longitude in decimal degrees, as received even if not valid
+    pub fn longitude(&self) -> f64 {
+        Coordinate::decode_unchecked(&self.coordinate).longitude()
+    }
+
+    /// Circular Error Probable (CEP) radius in km
+    pub fn cep_radius(&self) -> u32 {
+        self.cep_radius
     }
 }
 
@@ -196,27 +546,88 @@ impl InformationElement for Location {
         11
     }
 
-    fn write<W: std::io::Write>(&self, wtr: &mut W) -> Result<usize> {
-        todo!();
+    /// Fields of a Location, to annotate a hexdump
+    fn fields(&self) -> Vec<(&'static str, usize)> {
+        let mut fields = Coordinate::FIELDS.to_vec();
+        fields.push(("CEP radius", 4));
+        fields
+    }
+
+    /// Export a Location using a Write trait
+    fn write<W: Write>(&self, wtr: &mut W) -> Result<usize> {
         wtr.write_u8(self.identifier())?;
         wtr.write_u16::<BigEndian>(self.len())?;
-        wtr.write_all(&self.encode())?;
+        wtr.write_all(&self.coordinate)?;
         wtr.write_u32::<BigEndian>(self.cep_radius)?;
         Ok(14)
     }
 }
 
+impl core::fmt::Display for Location {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        writeln!(f, "Latitude: {:.5}°", self.latitude())?;
+        writeln!(f, "Longitude: {:.5}°", self.longitude())?;
+        write!(f, "CEP radius: {} km", self.cep_radius)
+    }
+}
+
 #[cfg(test)]
 mod test_location {
-    use super::{InformationElement, Location};
+    use super::{Coordinate, InformationElement, Location};
 
     #[test]
-    fn decode() {
+    fn read() {
         let buffer = [
             0x03, 0x00, 0x0b, 0x01, 0x21, 0x28, 0x47, 0x76, 0x7f, 0x06, 0x00, 0x01, 0x00, 0x00,
         ];
-        let location = Location::decode(&buffer).unwrap();
+        let location = Location::from_reader(buffer.as_slice()).unwrap();
         assert_eq!(location.len(), 11);
-        assert_eq!(location.cep_radius, 65536);
+        assert_eq!(location.cep_radius(), 65536);
+        assert!((location.latitude() - 33.17185).abs() < 1e-9);
+        assert!((location.longitude() + 118.541966).abs() < 1e-6);
+    }
+
+    #[test]
+    fn write() {
+        let location = Location::new(Coordinate::new(15.0, -38.0).unwrap(), 10);
+        assert_eq!(
+            location.to_vec(),
+            [0x03, 0x00, 0x0b, 0x01, 0x0f, 0x00, 0x00, 0x26, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0a]
+        );
+    }
+
+    #[test]
+    fn roundtrip_write_n_read() {
+        let buffer = [
+            0x03, 0x00, 0x0b, 0x01, 0x21, 0x28, 0x47, 0x76, 0x7f, 0x06, 0x00, 0x01, 0x00, 0x00,
+        ];
+        let location = Location::from_reader(buffer.as_slice()).unwrap();
+        assert_eq!(location.to_vec(), buffer);
+    }
+
+    #[test]
+    // Kept as received, only the accessor fails
+    fn read_invalid_coordinate() {
+        let buffer = [
+            0x03, 0x00, 0x0b, 0x00, 0x5b, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0a,
+        ];
+        let location = Location::from_reader(buffer.as_slice()).unwrap();
+        assert_eq!(location.latitude(), 91.0);
+        assert!(matches!(
+            location.coordinate(),
+            Err(crate::error::Error::InvalidCoordinate { .. })
+        ));
+        assert_eq!(location.to_vec(), buffer);
+    }
+
+    #[test]
+    fn read_wrong_iei() {
+        let buffer = [
+            0x02, 0x00, 0x0b, 0x01, 0x21, 0x28, 0x47, 0x76, 0x7f, 0x06, 0x00, 0x01, 0x00, 0x00,
+        ];
+        assert!(matches!(
+            Location::from_reader(buffer.as_slice()),
+            Err(crate::error::Error::WrongIEType(_, 0x03, 0x02))
+        ));
     }
 }
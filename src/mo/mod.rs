@@ -7,7 +7,7 @@
 //! * 0x03 Lat/Lon Location Information IEI
 //! * 0x05 Confirmation Message IEI
 //!
-//! ## Example of an MT Message
+//! ## Example of an MO Message
 //!
 //! Protocol Revision Number        1   1
 //! Overall Message Length          2   31
@@ -23,27 +23,47 @@
 //! MO Payload Length               2   12
 //! MO Payload                      12  "Hello World!"
 
+mod confirmation;
 mod header;
 mod location;
 mod payload;
 
-use std::io::Read;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
 
-use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use byteorder::BigEndian;
+use chrono::{DateTime, Utc};
+use log::debug;
 
+use crate::diff::ElementFields;
 use crate::error::{Error, Result};
-use crate::InformationElement;
-use header::Header;
-use location::Location;
-use payload::Payload;
+use crate::hexdump::HexDump;
+use crate::io::{Read, ReadBytesExt, Write, WriteBytesExt};
+use crate::modem::check_payload;
+use crate::{Imei, InformationElement, Modem};
+pub use confirmation::{Confirmation, ConfirmationBuilder};
+use header::HeaderBuilder;
+pub use header::{Header, SessionStatus};
+#[cfg(feature = "serde")]
+pub use location::GeoJsonPoint;
+pub use location::{Coordinate, Dms, Location};
+pub(crate) use payload::MAX_PAYLOAD_LEN;
+use payload::{Payload, PayloadBuilder};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[allow(dead_code)]
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 enum InformationElementType {
     H(Header),
     L(Location),
     P(Payload),
+    C(Confirmation),
+    /// An Information Element not known by this library, kept as received
+    Unknown {
+        iei: u8,
+        #[cfg_attr(feature = "serde", serde(with = "crate::encoding::element"))]
+        bytes: Vec<u8>,
+    },
 }
 
 impl InformationElement for InformationElementType {
@@ -52,6 +72,8 @@ impl InformationElement for InformationElementType {
             InformationElementType::H(element) => element.identifier(),
             InformationElementType::L(element) => element.identifier(),
             InformationElementType::P(element) => element.identifier(),
+            InformationElementType::C(element) => element.identifier(),
+            InformationElementType::Unknown { iei, .. } => *iei,
         }
     }
 
@@ -60,36 +82,86 @@ impl InformationElement for InformationElementType {
             InformationElementType::H(element) => element.len(),
             InformationElementType::L(element) => element.len(),
             InformationElementType::P(element) => element.len(),
+            InformationElementType::C(element) => element.len(),
+            InformationElementType::Unknown { bytes, .. } => {
+                // Bounded by parsing and deserialization
+                bytes.len().try_into().expect("Unknown element too large")
+            }
         }
     }
 
-    fn write<W: std::io::Write>(&self, wtr: &mut W) -> Result<usize> {
+    fn write<W: Write>(&self, wtr: &mut W) -> Result<usize> {
         match self {
             InformationElementType::H(element) => element.write(wtr),
             InformationElementType::L(element) => element.write(wtr),
             InformationElementType::P(element) => element.write(wtr),
+            InformationElementType::C(element) => element.write(wtr),
+            InformationElementType::Unknown { iei, bytes } => {
+                wtr.write_u8(*iei)?;
+                wtr.write_u16::<BigEndian>(self.len())?;
+                wtr.write_all(bytes)?;
+                Ok(3 + bytes.len())
+            }
+        }
+    }
+
+    fn fields(&self) -> Vec<(&'static str, usize)> {
+        match self {
+            InformationElementType::H(element) => element.fields(),
+            InformationElementType::L(element) => element.fields(),
+            InformationElementType::P(element) => element.fields(),
+            InformationElementType::C(element) => element.fields(),
+            InformationElementType::Unknown { .. } => {
+                vec![("Contents", usize::from(self.len()))]
+            }
         }
     }
 }
 
 impl InformationElementType {
+    /// Name of the Information Element
+    fn name(&self) -> &'static str {
+        match self {
+            InformationElementType::H(_) => "MO Header",
+            InformationElementType::L(_) => "MO Location Information",
+            InformationElementType::P(_) => "MO Payload",
+            InformationElementType::C(_) => "MO Confirmation",
+            InformationElementType::Unknown { .. } => "Unknown",
+        }
+    }
+
     /// Parse a InformationElementType from a Read trait
-    pub(super) fn from_reader<R: std::io::Read>(mut rdr: R) -> Result<Self> {
+    pub(super) fn from_reader<R: Read>(mut rdr: R) -> Result<Self> {
         let iei = rdr.read_u8()?;
         let buffer = [iei; 1];
         let buffer = buffer.chain(rdr);
         let element = match iei {
-            0x01 => {
-                let header = Header::from_reader(buffer).unwrap();
-                InformationElementType::H(header)
-            }
-            0x02 => {
-                let payload = Payload::from_reader(buffer).unwrap();
-                InformationElementType::P(payload)
-            }
-            _ => return Err(Error::Undefined),
+            0x01 => Header::from_reader(buffer).map(InformationElementType::H),
+            0x02 => Payload::from_reader(buffer).map(InformationElementType::P),
+            0x03 => Location::from_reader(buffer).map(InformationElementType::L),
+            0x05 => Confirmation::from_reader(buffer).map(InformationElementType::C),
+            _ => InformationElementType::unknown_from_reader(buffer),
         };
-        Ok(element)
+        element.map_err(|e| e.truncated(iei))
+    }
+
+    /// Parse an Information Element not known by this library
+    ///
+    /// The element is kept as received, so that it can be written back
+    /// unchanged.
+    fn unknown_from_reader<R: Read>(mut rdr: R) -> Result<Self> {
+        let iei = rdr.read_u8()?;
+        debug!("Unknown IEI: 0x{:02x}", iei);
+        let n = rdr.read_u16::<BigEndian>()?;
+        let mut bytes = vec![0; usize::from(n)];
+        rdr.read_exact(&mut bytes)?;
+        Ok(InformationElementType::Unknown { iei, bytes })
+    }
+}
+
+impl From<Confirmation> for InformationElementType {
+    fn from(confirmation: Confirmation) -> Self {
+        InformationElementType::C(confirmation)
     }
 }
 
@@ -99,20 +171,58 @@ impl From<Header> for InformationElementType {
     }
 }
 
+impl From<Location> for InformationElementType {
+    fn from(location: Location) -> Self {
+        InformationElementType::L(location)
+    }
+}
+
 impl From<Payload> for InformationElementType {
     fn from(payload: Payload) -> Self {
         InformationElementType::P(payload)
     }
 }
 
+impl core::fmt::Display for InformationElementType {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "IEI 0x{:02x} ", self.identifier())?;
+        let content: &dyn core::fmt::Display = match self {
+            InformationElementType::H(h) => h,
+            InformationElementType::L(l) => l,
+            InformationElementType::P(p) => p,
+            InformationElementType::C(c) => c,
+            InformationElementType::Unknown { bytes, .. } => {
+                write!(f, "{} ({} bytes): ", self.name(), self.len())?;
+                return crate::describe::write_preview(f, bytes);
+            }
+        };
+        write!(f, "{} ({} bytes)", self.name(), self.len())?;
+        crate::describe::write_indented(f, content, "  ")
+    }
+}
+
+/// Human-readable report of the Message, one Information Element at a time
+impl core::fmt::Display for MOMessage {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "MO Message ({} bytes)", self.total_size())?;
+        for e in &self.elements {
+            crate::describe::write_indented(f, e, "  ")?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct MOMessage {
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::encoding::deserialize_elements")
+    )]
     elements: Vec<InformationElementType>,
 }
 
 impl MOMessage {
-    #[allow(dead_code)]
     fn new() -> MOMessage {
         MOMessage {
             elements: Vec::new(),
@@ -121,16 +231,18 @@ impl MOMessage {
 
     /// Overall Message Length
     fn len(&self) -> u16 {
-        self.elements.iter().map(|e| 3 + e.len()).sum::<u16>()
+        // Bounded by the builder and deserialization
+        (self.total_size() - 3)
+            .try_into()
+            .expect("Message too large")
     }
 
-    #[allow(dead_code)]
     fn total_size(&self) -> usize {
-        3 + self.len() as usize
+        3 + self.elements.iter().map(|e| e.total_size()).sum::<usize>()
     }
 
     // Write the full message
-    fn write<W: std::io::Write>(&self, wtr: &mut W) -> Result<usize> {
+    fn write<W: Write>(&self, wtr: &mut W) -> Result<usize> {
         // Protocol version
         wtr.write_u8(1)?;
         // Message total length
@@ -145,40 +257,115 @@ impl MOMessage {
 
     /// Export MT-Message into a vector of u8
     pub fn to_vec(&self) -> Vec<u8> {
+        self.try_to_vec()
+            .expect("Failed to write Information Element to a vec.")
+    }
+
+    /// Export into a vector of u8, failing if anything can't be encoded
+    pub(crate) fn try_to_vec(&self) -> Result<Vec<u8>> {
         let mut buffer: Vec<u8> = Vec::new();
-        self.write(&mut buffer)
-            .expect("Failed to write Information Element to a vec.");
-        buffer
+        self.write(&mut buffer)?;
+        Ok(buffer)
     }
-    /// Parse bytes from a buffer to compose an MTMessage
-    pub fn from_reader<R: std::io::Read>(mut rdr: R) -> Result<Self> {
+
+    /// Annotated hexdump of the encoded message
+    ///
+    /// Each byte range is labelled with what it is, such as the Information
+    /// Element Identifiers, their lengths, and each of their fields. Fails
+    /// if the message can't be encoded.
+    pub fn hexdump(&self) -> Result<String> {
+        let mut dump = HexDump::new(self.try_to_vec()?);
+        dump.message_header(self.len());
+        for e in &self.elements {
+            dump.element(e.name(), e);
+        }
+        Ok(dump.finish())
+    }
+
+    /// Information Elements split in their fields, to be compared
+    pub(crate) fn element_fields(&self) -> Result<Vec<ElementFields>> {
+        self.elements
+            .iter()
+            .map(|e| ElementFields::new(e.name(), e))
+            .collect()
+    }
+
+    /// Encode into a slice of bytes, returning the number of bytes written
+    pub fn encode(&self, buffer: &mut [u8]) -> Result<usize> {
+        let required = self.total_size();
+        if buffer.len() < required {
+            return Err(Error::BufferTooSmall {
+                required,
+                available: buffer.len(),
+            });
+        }
+        let mut wtr = &mut buffer[..required];
+        self.write(&mut wtr)
+    }
+
+    /// Parse an MOMessage from a slice of bytes
+    pub fn from_bytes(buffer: &[u8]) -> Result<Self> {
+        Self::read(buffer)
+    }
+
+    /// Parse an MOMessage from a reader
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: std::io::Read>(rdr: R) -> Result<Self> {
+        Self::read(rdr)
+    }
+
+    /// Parse bytes from a buffer to compose an MOMessage
+    pub(crate) fn read<R: Read>(mut rdr: R) -> Result<Self> {
         // Protocol version
         let version = rdr.read_u8()?;
         // Expects version 1
-        assert_eq!(version, 1);
+        if version != 1 {
+            return Err(Error::UnsupportedVersion { version, offset: 0 });
+        }
         // Message total length
-        let length = rdr.read_u16::<BigEndian>().expect("Failed to read length") as usize;
+        let length = usize::from(rdr.read_u16::<BigEndian>()?);
 
         let mut msg = MOMessage { elements: vec![] };
         let mut n = 0;
         while n < length {
-            let element = InformationElementType::from_reader(&mut rdr)?;
+            let element = match InformationElementType::from_reader(&mut rdr) {
+                Ok(element) => element,
+                // Input ended before the next IEI
+                Err(e) if e.is_eof() => {
+                    return Err(Error::MessageLengthMismatch {
+                        declared: length,
+                        actual: n,
+                    })
+                }
+                Err(e) => return Err(e.shift(3 + n)),
+            };
             n += element.total_size();
             msg.push(element);
         }
+        if n != length {
+            return Err(Error::MessageLengthMismatch {
+                declared: length,
+                actual: n,
+            });
+        }
 
         Ok(msg)
     }
 
+    pub fn builder() -> MOMessageBuilder {
+        MOMessageBuilder::default()
+    }
+
     /// Appends an element to the back of an MT-Message
     ///
-    /// This should be a good place to check for duplicates, i.e. try to
-    /// insert a second header for instance.
+    /// Nothing is checked here, a received message might not be compliant.
+    /// See [crate::Message::validate] for duplicates and other violations.
     fn push(&mut self, element: InformationElementType) {
         self.elements.push(element);
     }
 
-    fn header(&self) -> Option<&Header> {
+    /// Header element
+    pub fn header(&self) -> Option<&Header> {
         self.elements
             .iter()
             .find(|elem| matches!(elem, InformationElementType::H(_)))
@@ -191,9 +378,437 @@ impl MOMessage {
             })
     }
 
-    pub fn imei(&self) -> Option<[u8; 15]> {
+    pub fn imei(&self) -> Option<Imei> {
         self.header().map(|h| h.imei())
     }
+
+    /// Payload contents, if available
+    pub fn payload(&self) -> Option<&[u8]> {
+        self.elements.iter().find_map(|elem| match elem {
+            InformationElementType::P(p) => Some(p.payload()),
+            _ => None,
+        })
+    }
+
+    /// Location of the modem estimated by the Iridium Gateway
+    ///
+    /// The MO Location Information element is optional, thus it might not be
+    /// available.
+    pub fn location(&self) -> Option<&Location> {
+        self.elements.iter().find_map(|elem| match elem {
+            InformationElementType::L(l) => Some(l),
+            _ => None,
+        })
+    }
+
+    /// Confirmation element, if this is an MO confirmation message
+    pub fn confirmation(&self) -> Option<&Confirmation> {
+        self.elements.iter().find_map(|elem| match elem {
+            InformationElementType::C(c) => Some(c),
+            _ => None,
+        })
+    }
+
+    /// Compose the confirmation message acknowledging this MO message
+    ///
+    /// The Iridium Gateway expects the receiving server to answer each MO
+    /// session with an MO Confirmation. The reception is considered
+    /// successful if this message contains an MO Header. Use `to_vec()` on
+    /// the result to obtain the bytes to transmit.
+    pub fn acknowledgment(&self) -> MOMessage {
+        let mut msg = MOMessage::new();
+        msg.push(
+            Confirmation::builder()
+                .successful(self.header().is_some())
+                .build()
+                .expect("All Confirmation fields were defined")
+                .into(),
+        );
+        msg
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test_mo_message {
+    use super::{Error, InformationElementType, MOMessage, SessionStatus};
+    use chrono::{DateTime, Utc};
+
+    #[test]
+    fn location_from_reader() {
+        let buffer = [
+            0x01, 0x00, 0x3c, 0x01, 0x00, 0x1c, 0x00, 0x00, 0x27, 0x0f, 0x00, 0x01, 0x02, 0x03,
+            0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x00, 0x03, 0xe7,
+            0x00, 0x6f, 0x38, 0xce, 0x2c, 0x9c, 0x03, 0x00, 0x0b, 0x01, 0x21, 0x28, 0x47, 0x76,
+            0x7f, 0x06, 0x00, 0x01, 0x00, 0x00, 0x02, 0x00, 0x0c, 0x48, 0x65, 0x6c, 0x6c, 0x6f,
+            0x20, 0x57, 0x6f, 0x72, 0x6c, 0x64, 0x21,
+        ];
+        let msg = MOMessage::from_reader(buffer.as_slice()).unwrap();
+        let location = msg.location().unwrap();
+        assert!((location.latitude() - 33.17185).abs() < 1e-9);
+        assert!((location.longitude() + 118.541966).abs() < 1e-6);
+        assert_eq!(location.cep_radius(), 65536);
+
+        assert_eq!(msg.to_vec(), buffer);
+    }
+
+    #[test]
+    fn header_n_payload() {
+        let buffer = [
+            0x01, 0x00, 0x2e, 0x01, 0x00, 0x1c, 0x00, 0x00, 0x27, 0x0f, 0x00, 0x01, 0x02, 0x03,
+            0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x00, 0x03, 0xe7,
+            0x00, 0x6f, 0x38, 0xce, 0x2c, 0x9c, 0x02, 0x00, 0x0c, 0x48, 0x65, 0x6c, 0x6c, 0x6f,
+            0x20, 0x57, 0x6f, 0x72, 0x6c, 0x64, 0x21,
+        ];
+        let msg = MOMessage::from_reader(buffer.as_slice()).unwrap();
+        let header = msg.header().unwrap();
+        assert_eq!(header.cdr_uid(), 9999);
+        assert_eq!(
+            header.imei(),
+            [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]
+        );
+        assert_eq!(header.session_status(), &SessionStatus::Success);
+        assert_eq!(header.momsn(), 999);
+        assert_eq!(header.mtmsn(), 111);
+        assert_eq!(
+            header.time_of_session(),
+            "2000-03-14T12:12:12Z".parse::<DateTime<Utc>>().unwrap()
+        );
+        assert_eq!(msg.payload(), Some("Hello World!".as_bytes()));
+    }
+
+    #[test]
+    fn location_missing() {
+        let buffer = [
+            0x01, 0x00, 0x1f, 0x01, 0x00, 0x1c, 0x00, 0x00, 0x27, 0x0f, 0x00, 0x01, 0x02, 0x03,
+            0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x00, 0x03, 0xe7,
+            0x00, 0x6f, 0x38, 0xce, 0x2c, 0x9c,
+        ];
+        let msg = MOMessage::from_reader(buffer.as_slice()).unwrap();
+        assert!(msg.location().is_none());
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let buffer = [0x02, 0x00, 0x04, 0x05, 0x00, 0x01, 0x00];
+        assert!(matches!(
+            MOMessage::from_reader(buffer.as_slice()),
+            Err(Error::UnsupportedVersion {
+                version: 2,
+                offset: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn unknown_iei() {
+        let buffer = [
+            0x01, 0x00, 0x0a, 0x05, 0x00, 0x01, 0x01, 0x09, 0x00, 0x03, 0x0a, 0x0b, 0x0c,
+        ];
+        let msg = MOMessage::from_reader(buffer.as_slice()).unwrap();
+        assert!(msg.confirmation().unwrap().is_successful());
+        assert!(matches!(
+            &msg.elements[1],
+            InformationElementType::Unknown { iei: 0x09, bytes } if bytes == &[0x0a, 0x0b, 0x0c]
+        ));
+        assert_eq!(msg.to_vec(), buffer);
+    }
+
+    #[test]
+    fn truncated_unknown_iei() {
+        let buffer = [
+            0x01, 0x00, 0x0a, 0x05, 0x00, 0x01, 0x01, 0x09, 0x00, 0x03, 0x0a,
+        ];
+        assert!(matches!(
+            MOMessage::from_reader(buffer.as_slice()),
+            Err(Error::TruncatedElement {
+                iei: 0x09,
+                offset: 7
+            })
+        ));
+    }
+
+    #[test]
+    fn invalid_element_length() {
+        let buffer = [0x01, 0x00, 0x05, 0x05, 0x00, 0x02, 0x01, 0x00];
+        assert!(matches!(
+            MOMessage::from_reader(buffer.as_slice()),
+            Err(Error::InvalidIELength {
+                iei: 0x05,
+                length: 2,
+                offset: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn truncated_element() {
+        let buffer = [
+            0x01, 0x00, 0x1f, 0x01, 0x00, 0x1c, 0x00, 0x00, 0x27, 0x0f, 0x00, 0x01, 0x02, 0x03,
+        ];
+        assert!(matches!(
+            MOMessage::from_reader(buffer.as_slice()),
+            Err(Error::TruncatedElement {
+                iei: 0x01,
+                offset: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn length_mismatch() {
+        // Declared length shorter than the confirmation element
+        let buffer = [0x01, 0x00, 0x03, 0x05, 0x00, 0x01, 0x01];
+        assert!(matches!(
+            MOMessage::from_reader(buffer.as_slice()),
+            Err(Error::MessageLengthMismatch {
+                declared: 3,
+                actual: 4
+            })
+        ));
+
+        // Declared length longer than the available elements
+        let buffer = [0x01, 0x00, 0x08, 0x05, 0x00, 0x01, 0x01];
+        assert!(matches!(
+            MOMessage::from_reader(buffer.as_slice()),
+            Err(Error::MessageLengthMismatch {
+                declared: 8,
+                actual: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn acknowledgment() {
+        let buffer = [
+            0x01, 0x00, 0x1f, 0x01, 0x00, 0x1c, 0x00, 0x00, 0x27, 0x0f, 0x00, 0x01, 0x02, 0x03,
+            0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x00, 0x03, 0xe7,
+            0x00, 0x6f, 0x38, 0xce, 0x2c, 0x9c,
+        ];
+        let msg = MOMessage::from_reader(buffer.as_slice()).unwrap();
+        let ack = msg.acknowledgment();
+        assert!(ack.confirmation().unwrap().is_successful());
+        assert_eq!(ack.to_vec(), [0x01, 0x00, 0x04, 0x05, 0x00, 0x01, 0x01]);
+    }
+
+    #[test]
+    fn confirmation_from_reader() {
+        let buffer = [0x01, 0x00, 0x04, 0x05, 0x00, 0x01, 0x00];
+        let msg = MOMessage::from_reader(buffer.as_slice()).unwrap();
+        assert!(!msg.confirmation().unwrap().is_successful());
+        assert!(msg.imei().is_none());
+    }
+}
+
+pub struct MOMessageBuilder {
+    header: Option<HeaderBuilder>,
+    payload: Option<PayloadBuilder>,
+    location: Option<(f64, f64, u32)>,
+    confirmation: Option<ConfirmationBuilder>,
+    modem: Option<Modem>,
+}
+
+impl MOMessageBuilder {
+    fn default() -> MOMessageBuilder {
+        MOMessageBuilder {
+            header: None,
+            payload: None,
+            location: None,
+            confirmation: None,
+            modem: None,
+        }
+    }
+
+    fn header(mut self, f: impl FnOnce(HeaderBuilder) -> HeaderBuilder) -> Self {
+        self.header = Some(f(self.header.unwrap_or_default()));
+        self
+    }
+
+    pub fn cdr_uid(self, cdr_uid: u32) -> Self {
+        self.header(|h| h.cdr_uid(cdr_uid))
+    }
+
+    pub fn imei(self, imei: Imei) -> Self {
+        self.header(|h| h.imei(imei))
+    }
+
+    pub fn session_status(self, session_status: SessionStatus) -> Self {
+        self.header(|h| h.session_status(session_status))
+    }
+
+    pub fn momsn(self, momsn: u16) -> Self {
+        self.header(|h| h.momsn(momsn))
+    }
+
+    pub fn mtmsn(self, mtmsn: u16) -> Self {
+        self.header(|h| h.mtmsn(mtmsn))
+    }
+
+    pub fn time_of_session(self, time_of_session: DateTime<Utc>) -> Self {
+        self.header(|h| h.time_of_session(time_of_session))
+    }
+
+    pub fn payload(mut self, payload: Vec<u8>) -> Self {
+        self.payload = Some(PayloadBuilder::default().payload(payload));
+        self
+    }
+
+    /// Location, in decimal degrees, and its CEP radius in km
+    pub fn location(mut self, latitude: f64, longitude: f64, cep_radius: u32) -> Self {
+        self.location = Some((latitude, longitude, cep_radius));
+        self
+    }
+
+    /// Modem sending the message, to check the payload against its limit
+    pub fn modem(mut self, modem: Modem) -> Self {
+        self.modem = Some(modem);
+        self
+    }
+
+    /// Compose an MO Confirmation message instead
+    ///
+    /// A confirmation is sent alone, thus it can't be combined with any other
+    /// element.
+    pub fn confirmation(mut self, successful: bool) -> Self {
+        self.confirmation = Some(ConfirmationBuilder::default().successful(successful));
+        self
+    }
+
+    pub fn build(self) -> Result<MOMessage> {
+        let mut msg = MOMessage::new();
+        if let Some(confirmation) = self.confirmation {
+            if self.header.is_some() || self.payload.is_some() || self.location.is_some() {
+                return Err(Error::InvalidMessage(
+                    "MO Confirmation can't be combined with other elements".to_string(),
+                ));
+            }
+            msg.push(confirmation.build()?.into());
+            return Ok(msg);
+        }
+
+        match self.header {
+            Some(header) => msg.push(header.build()?.into()),
+            None => {
+                return Err(Error::InvalidMessage("Missing MO Header".to_string()));
+            }
+        }
+        if let Some(payload) = self.payload {
+            let payload = payload.build()?;
+            if let Some(modem) = self.modem {
+                check_payload(payload.payload().len(), modem.max_mo_payload())?;
+            }
+            msg.push(payload.into());
+        }
+        if let Some((latitude, longitude, cep_radius)) = self.location {
+            let coordinate = Coordinate::new(latitude, longitude)?;
+            msg.push(Location::new(coordinate, cep_radius).into());
+        }
+        Ok(msg)
+    }
+}
+
+#[cfg(test)]
+mod test_mo_message_builder {
+    use super::{Error, MOMessage, MOMessageBuilder, SessionStatus};
+    use chrono::{DateTime, Utc};
+
+    fn builder() -> MOMessageBuilder {
+        MOMessage::builder()
+            .cdr_uid(9999)
+            .imei([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14].into())
+            .session_status(SessionStatus::Success)
+            .momsn(999)
+            .mtmsn(111)
+            .time_of_session("2000-03-14T12:12:12Z".parse::<DateTime<Utc>>().unwrap())
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn build() {
+        let msg = builder()
+            .payload("Hello World!".into())
+            .location(15.0, -38.0, 10)
+            .build()
+            .unwrap();
+
+        assert_eq!(msg.header().unwrap().momsn(), 999);
+        assert_eq!(msg.payload(), Some("Hello World!".as_bytes()));
+        assert_eq!(msg.location().unwrap().cep_radius(), 10);
+
+        let roundtrip = MOMessage::from_reader(msg.to_vec().as_slice()).unwrap();
+        assert_eq!(roundtrip.to_vec(), msg.to_vec());
+    }
+
+    #[test]
+    fn build_invalid_location() {
+        let msg = builder().location(91.0, -38.0, 10).build();
+        assert!(matches!(msg, Err(Error::InvalidCoordinate { .. })));
+    }
+
+    #[test]
+    // The time of session is encoded as seconds since 1970 in 4 bytes
+    fn build_invalid_time_of_session() {
+        for time in ["1960-01-01T00:00:00Z", "2107-01-01T00:00:00Z"] {
+            let msg = builder()
+                .time_of_session(time.parse::<DateTime<Utc>>().unwrap())
+                .build();
+            assert!(matches!(msg, Err(Error::InvalidTimeOfSession(_))));
+        }
+        let latest = DateTime::<Utc>::from_timestamp(u32::MAX.into(), 0).unwrap();
+        assert!(builder().time_of_session(latest).build().is_ok());
+    }
+
+    #[test]
+    fn build_header_only() {
+        let msg = builder().build().unwrap();
+        assert_eq!(msg.total_size(), 34);
+    }
+
+    #[test]
+    fn build_for_modem() {
+        use crate::Modem;
+
+        let msg = builder()
+            .payload(vec![0x42; 340])
+            .modem(Modem::Iridium9602)
+            .build();
+        assert!(msg.is_ok());
+
+        let msg = builder()
+            .payload(vec![0x42; 341])
+            .modem(Modem::Iridium9602)
+            .build();
+        assert!(matches!(
+            msg,
+            Err(Error::PayloadOversized {
+                length: 341,
+                limit: 340
+            })
+        ));
+    }
+
+    #[test]
+    fn build_missing_header() {
+        let msg = MOMessage::builder().payload(vec![0x42]).build();
+        assert!(matches!(msg, Err(Error::InvalidMessage(_))));
+    }
+
+    #[test]
+    fn build_incomplete_header() {
+        let msg = MOMessage::builder().cdr_uid(9999).build();
+        assert!(matches!(msg, Err(Error::UninitializedFieldError(_))));
+    }
+
+    #[test]
+    fn build_confirmation() {
+        let msg = MOMessage::builder().confirmation(true).build().unwrap();
+        assert_eq!(msg.to_vec(), [0x01, 0x00, 0x04, 0x05, 0x00, 0x01, 0x01]);
+    }
+
+    #[test]
+    fn build_confirmation_with_header() {
+        let msg = builder().confirmation(true).build();
+        assert!(matches!(msg, Err(Error::InvalidMessage(_))));
+    }
 }
 
 #[cfg(all(test, feature = "serde"))]
@@ -233,3 +848,96 @@ mod test_mo_information_element_serde {
         assert_eq!(ie, roundtrip);
     }
 }
+
+#[cfg(test)]
+mod test_mo_display {
+    use super::{MOMessage, SessionStatus};
+    use crate::Imei;
+    use chrono::{DateTime, Utc};
+
+    #[test]
+    fn describe() {
+        let msg = MOMessage::builder()
+            .cdr_uid(9999)
+            .imei("300234010753376".parse::<Imei>().unwrap())
+            .session_status(SessionStatus::Success)
+            .momsn(999)
+            .mtmsn(111)
+            .time_of_session("2000-03-14T12:12:12Z".parse::<DateTime<Utc>>().unwrap())
+            .payload("Hello World!".into())
+            .location(15.5, -38.25, 10)
+            .build()
+            .unwrap();
+
+        let report = msg.to_string();
+        assert!(report.starts_with("MO Message ("));
+        assert!(report.contains("\n  IEI 0x01 MO Header (28 bytes)\n    CDR reference: 9999\n"));
+        assert!(report.contains("\n    IMEI: 300234010753376\n"));
+        assert!(report.contains("\n    Session status: Success (Session completed successfully)\n"));
+        assert!(report.contains("\n    Time of session: 2000-03-14T12:12:12Z"));
+        assert!(report.contains("\n    Payload: \"Hello World!\""));
+        assert!(report.contains("\n    Latitude: 15.50000°\n    Longitude: -38.25000°\n"));
+        assert!(report.contains("\n    CEP radius: 10 km"));
+    }
+}
+
+#[cfg(test)]
+mod test_mo_hexdump {
+    use super::{MOMessage, SessionStatus};
+    use chrono::{DateTime, Utc};
+
+    #[test]
+    fn hexdump() {
+        let msg = MOMessage::builder()
+            .cdr_uid(9999)
+            .imei([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14].into())
+            .session_status(SessionStatus::Success)
+            .momsn(999)
+            .mtmsn(111)
+            .time_of_session("2000-03-14T12:12:12Z".parse::<DateTime<Utc>>().unwrap())
+            .payload((0..20).collect())
+            .location(15.5, -38.25, 10)
+            .build()
+            .unwrap();
+
+        let dump = msg.hexdump().unwrap();
+        let labels: Vec<_> = dump.lines().filter_map(|line| line.get(55..)).collect();
+        assert_eq!(
+            labels,
+            [
+                "Protocol revision",
+                "Message length: 68",
+                "IEI: MO Header",
+                "IE length: 28",
+                "CDR reference",
+                "IMEI",
+                "Session status",
+                "MOMSN",
+                "MTMSN",
+                "Time of session",
+                "IEI: MO Payload",
+                "IE length: 20",
+                "Payload",
+                "IEI: MO Location Information",
+                "IE length: 11",
+                "Orientation",
+                "Latitude degrees",
+                "Latitude thousandths of minute",
+                "Longitude degrees",
+                "Longitude thousandths of minute",
+                "CEP radius",
+            ]
+        );
+        // Every byte is listed exactly once
+        let bytes: usize = dump
+            .lines()
+            .map(|line| {
+                line.get(6..53)
+                    .unwrap_or(&line[6..])
+                    .split_whitespace()
+                    .count()
+            })
+            .sum();
+        assert_eq!(bytes, msg.total_size());
+    }
+}
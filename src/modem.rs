@@ -0,0 +1,77 @@
+//! Iridium modem models and their payload limits
+//!
+//! The Direct-IP protocol accepts MO payloads up to 1960 bytes and MT
+//! payloads up to 1890 bytes, but some modems only support smaller
+//! messages. An oversized MT payload is only reported later, by the
+//! gateway's confirmation or a failed delivery, so it is better to check it
+//! while composing the message.
+
+use crate::error::{Error, Result};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+/// Modem model defining the largest payloads that it can handle
+pub enum Modem {
+    /// Iridium 9601 SBD transceiver
+    Iridium9601,
+    /// Iridium 9602 SBD transceiver
+    Iridium9602,
+    /// Iridium 9603 SBD transceiver
+    Iridium9603,
+    /// Iridium 9523 L-band transceiver
+    Iridium9523,
+    /// Any other model, limited to the protocol's maximum
+    Custom { max_mo: usize, max_mt: usize },
+}
+
+impl Modem {
+    /// Largest MO payload, in bytes
+    pub fn max_mo_payload(&self) -> usize {
+        match self {
+            Modem::Iridium9601 | Modem::Iridium9602 | Modem::Iridium9603 => 340,
+            Modem::Iridium9523 => crate::mo::MAX_PAYLOAD_LEN,
+            Modem::Custom { max_mo, .. } => (*max_mo).min(crate::mo::MAX_PAYLOAD_LEN),
+        }
+    }
+
+    /// Largest MT payload, in bytes
+    pub fn max_mt_payload(&self) -> usize {
+        match self {
+            Modem::Iridium9601 | Modem::Iridium9602 | Modem::Iridium9603 => 270,
+            Modem::Iridium9523 => crate::mt::MAX_PAYLOAD_LEN,
+            Modem::Custom { max_mt, .. } => (*max_mt).min(crate::mt::MAX_PAYLOAD_LEN),
+        }
+    }
+}
+
+/// Verify that a payload of `length` bytes is within `limit`
+pub(crate) fn check_payload(length: usize, limit: usize) -> Result<()> {
+    if length > limit {
+        return Err(Error::PayloadOversized { length, limit });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_modem {
+    use super::Modem;
+
+    #[test]
+    fn limits() {
+        assert_eq!(Modem::Iridium9602.max_mo_payload(), 340);
+        assert_eq!(Modem::Iridium9603.max_mt_payload(), 270);
+        assert_eq!(Modem::Iridium9523.max_mo_payload(), 1960);
+        assert_eq!(Modem::Iridium9523.max_mt_payload(), 1890);
+    }
+
+    #[test]
+    // A custom modem can't go beyond the protocol
+    fn custom() {
+        let modem = Modem::Custom {
+            max_mo: 100,
+            max_mt: 5000,
+        };
+        assert_eq!(modem.max_mo_payload(), 100);
+        assert_eq!(modem.max_mt_payload(), 1890);
+    }
+}
@@ -1,11 +1,16 @@
-use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use byteorder::BigEndian;
 use derive_builder::Builder;
 
 use crate::error::{Error, Result};
-use crate::InformationElement;
+use crate::io::{Read, ReadBytesExt, Write, WriteBytesExt};
+use crate::{Imei, InformationElement};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub enum MessageStatus {
     // Successful, order of message in the MT message queue starting on 0
     // Currently, the maximum value is 50
@@ -39,9 +44,7 @@ pub enum MessageStatus {
 impl MessageStatus {
     fn decode(status: i16) -> Result<MessageStatus> {
         if (0..=50).contains(&status) {
-            return Ok(MessageStatus::SuccessfulQueueOrder(
-                status.try_into().unwrap(),
-            ));
+            return Ok(MessageStatus::SuccessfulQueueOrder(status as u8));
         }
         match status {
             -1 => Ok(MessageStatus::InvalidIMEI),
@@ -60,11 +63,11 @@ impl MessageStatus {
         }
     }
 
-    fn from_reader<R: std::io::Read>(mut rdr: R) -> Result<MessageStatus> {
+    fn from_reader<R: Read>(mut rdr: R) -> Result<MessageStatus> {
         MessageStatus::decode(rdr.read_i16::<BigEndian>()?)
     }
 
-    fn write<W: std::io::Write>(&self, wtr: &mut W) -> Result<usize> {
+    fn write<W: Write>(&self, wtr: &mut W) -> Result<usize> {
         let status = match self {
             MessageStatus::SuccessfulQueueOrder(n) => i16::from(*n),
             MessageStatus::InvalidIMEI => -1,
@@ -84,15 +87,14 @@ impl MessageStatus {
         Ok(2)
     }
 
-    #[allow(dead_code)]
     /// True if message delivery was confirmed
-    fn is_successful(&self) -> bool {
+    pub fn is_successful(&self) -> bool {
         matches!(self, MessageStatus::SuccessfulQueueOrder(_))
     }
 }
 
-impl std::fmt::Display for MessageStatus {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+impl core::fmt::Display for MessageStatus {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
         match self {
             MessageStatus::SuccessfulQueueOrder(n) => {
                 write!(f, "Success, queued in position {}.", n)
@@ -144,13 +146,14 @@ mod test_message_status_serde {
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Builder, Debug, PartialEq, Eq)]
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Hash)]
 #[builder(pattern = "owned", build_fn(error = "crate::error::Error"))]
 pub struct Confirmation {
     // From Client (not MTMSN)
     client_msg_id: u32,
     // ASCII Numeric Characters
-    imei: [u8; 15],
+    #[builder(setter(into))]
+    imei: Imei,
     // 0 – 4294967295
     // It will be zero when there is an error in processing the message
     id_reference: u32,
@@ -174,11 +177,20 @@ impl InformationElement for Confirmation {
         25
     }
 
-    fn write<W: std::io::Write>(&self, wtr: &mut W) -> Result<usize> {
+    fn fields(&self) -> Vec<(&'static str, usize)> {
+        vec![
+            ("Client message ID", 4),
+            ("IMEI", 15),
+            ("Auto ID reference", 4),
+            ("Message status", 2),
+        ]
+    }
+
+    fn write<W: Write>(&self, wtr: &mut W) -> Result<usize> {
         wtr.write_u8(0x44)?;
         wtr.write_u16::<BigEndian>(self.len())?;
         wtr.write_u32::<BigEndian>(self.client_msg_id)?;
-        wtr.write_all(&self.imei)?;
+        wtr.write_all(self.imei.as_bytes())?;
         wtr.write_u32::<BigEndian>(self.id_reference)?;
         // Shall we recover n written bytes and confirm that it was 2?
         let n = self.message_status.write(wtr)?;
@@ -190,15 +202,24 @@ impl InformationElement for Confirmation {
 impl Confirmation {
     #[allow(dead_code)]
     /// Parse a DispositionFlags from a Read trait
-    pub(super) fn from_reader<R: std::io::Read>(mut rdr: R) -> Result<Confirmation> {
+    pub(super) fn from_reader<R: Read>(mut rdr: R) -> Result<Confirmation> {
         let iei = rdr.read_u8()?;
-        assert_eq!(iei, 0x44);
+        if iei != 0x44 {
+            return Err(Error::WrongIEType("MT-Confirmation".to_string(), 0x44, iei));
+        }
         let len = rdr.read_u16::<BigEndian>()?;
-        assert_eq!(len, 25);
+        if len != 25 {
+            return Err(Error::InvalidIELength {
+                iei,
+                length: len,
+                offset: 1,
+            });
+        }
 
         let client_msg_id = rdr.read_u32::<BigEndian>()?;
         let mut imei = [0; 15];
         rdr.read_exact(&mut imei)?;
+        let imei = Imei::from(imei);
         let id_reference = rdr.read_u32::<BigEndian>()?;
         let message_status = MessageStatus::from_reader(rdr)?;
         Ok(Confirmation {
@@ -209,20 +230,45 @@ impl Confirmation {
         })
     }
 
-    pub fn message_status(&self) -> &MessageStatus {
-        &self.message_status
+    /// Client message ID, as defined in the MT Header
+    pub fn client_msg_id(&self) -> u32 {
+        self.client_msg_id
     }
 
-    pub(crate) fn imei(&self) -> [u8; 15] {
+    /// IMEI of the MT message destination
+    pub fn imei(&self) -> Imei {
         self.imei
     }
 
-    #[allow(dead_code)]
-    pub(crate) fn builder() -> ConfirmationBuilder {
+    /// Auto ID reference assigned by the Gateway
+    ///
+    /// It will be zero when there is an error in processing the message.
+    pub fn id_reference(&self) -> u32 {
+        self.id_reference
+    }
+
+    pub fn message_status(&self) -> &MessageStatus {
+        &self.message_status
+    }
+
+    pub fn builder() -> ConfirmationBuilder {
         ConfirmationBuilder::default()
     }
 }
 
+impl core::fmt::Display for Confirmation {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        writeln!(f, "Client message ID: {}", self.client_msg_id)?;
+        writeln!(f, "IMEI: {}", self.imei)?;
+        writeln!(f, "ID reference: {}", self.id_reference)?;
+        write!(
+            f,
+            "Message status: {:?} ({})",
+            self.message_status, self.message_status
+        )
+    }
+}
+
 #[cfg(test)]
 mod test_mt_confirmation {
     use super::{Confirmation, InformationElement, MessageStatus};
@@ -231,7 +277,7 @@ mod test_mt_confirmation {
     fn confirmation_write() {
         let confirmation = Confirmation {
             client_msg_id: 9999,
-            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14],
+            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14].into(),
             id_reference: 4294967295,
             message_status: MessageStatus::MTMSNOutOfRange,
         };
@@ -257,7 +303,7 @@ mod test_confirmation_serde {
     fn roundtrip() {
         let confirmation = Confirmation {
             client_msg_id: 9999,
-            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14],
+            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14].into(),
             id_reference: 4294967295,
             message_status: MessageStatus::SuccessfulQueueOrder(42),
         };
@@ -304,7 +350,7 @@ mod test_mt_confirmation_builder {
     fn roundtrip_write_n_read() {
         let confirmation = Confirmation {
             client_msg_id: 9999,
-            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14],
+            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14].into(),
             id_reference: 4294967295,
             message_status: MessageStatus::SuccessfulQueueOrder(42),
         };
@@ -4,14 +4,20 @@
 //! compose a mobile terminated message. It is defined by an information
 //! element identifier (IEI) with value 0x41.
 
-use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use byteorder::BigEndian;
 use derive_builder::Builder;
 
 use crate::error::Error;
-use crate::InformationElement;
+use crate::io::{Read, ReadBytesExt, Write, WriteBytesExt};
+use crate::{Imei, InformationElement};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Builder, Clone, Debug, PartialEq, Eq)]
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Hash)]
+#[builder(pattern = "owned", build_fn(error = "crate::error::Error"))]
 /// Disposition Flags
 ///
 /// Flags:
@@ -58,7 +64,7 @@ impl DispositionFlags {
     /// - All non used bits are ignored. It might be useful to consider a more
     ///   strict approach, where this would fail if a non-expected bit is
     ///   activated.
-    fn decode(code: u16) -> Self {
+    pub(crate) fn decode(code: u16) -> Self {
         let flush_queue = matches!(code & 0b0000_0000_0000_0001, 1);
         let send_ring_alert = matches!(code & 0b0000_0000_0000_0010, 2);
         let update_location = matches!(code & 0b0000_0000_0000_1000, 8);
@@ -75,7 +81,7 @@ impl DispositionFlags {
     }
 
     /// Parse a DispositionFlags from a Read trait
-    fn from_reader<R: std::io::Read>(mut rdr: R) -> Result<Self, Error> {
+    fn from_reader<R: Read>(mut rdr: R) -> Result<Self, Error> {
         let code = rdr.read_u16::<BigEndian>()?;
         Ok(DispositionFlags::decode(code))
     }
@@ -90,10 +96,55 @@ impl DispositionFlags {
     }
 
     /// Save a DispositionFlags using a Write trait
-    fn write<W: std::io::Write>(&self, wtr: &mut W) -> Result<usize, Error> {
+    fn write<W: Write>(&self, wtr: &mut W) -> Result<usize, Error> {
         wtr.write_u16::<BigEndian>(self.encode())?;
         Ok(2)
     }
+
+    /// Delete all MT payloads in the SSD's MT queue
+    pub fn flush_queue(&self) -> bool {
+        self.flush_queue
+    }
+
+    /// Send ring alert with no associated MT payload
+    pub fn send_ring_alert(&self) -> bool {
+        self.send_ring_alert
+    }
+
+    /// Update SSD location with given lat/lon values
+    pub fn update_location(&self) -> bool {
+        self.update_location
+    }
+
+    /// Place the associated MT payload in front of queue
+    pub fn high_priority(&self) -> bool {
+        self.high_priority
+    }
+
+    /// Use the value in the Unique ID field as the MTMSN
+    pub fn assign_mtmsn(&self) -> bool {
+        self.assign_mtmsn
+    }
+}
+
+impl core::fmt::Display for DispositionFlags {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        let flags = [
+            (self.flush_queue, "Flush MT Queue"),
+            (self.send_ring_alert, "Send Ring Alert"),
+            (self.update_location, "Update SSD Location"),
+            (self.high_priority, "High Priority"),
+            (self.assign_mtmsn, "Assign MTMSN"),
+        ];
+        let mut active = flags.iter().filter(|(on, _)| *on).map(|(_, name)| name);
+        match active.next() {
+            None => write!(f, "None"),
+            Some(name) => {
+                write!(f, "{}", name)?;
+                active.try_for_each(|name| write!(f, ", {}", name))
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -285,7 +336,7 @@ mod test_disposition_flags_serde {
 }
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Builder, Debug, PartialEq)]
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Hash)]
 #[builder(pattern = "owned", build_fn(error = "crate::error::Error"))]
 /// Mobile Terminated Header
 ///
@@ -304,24 +355,34 @@ mod test_disposition_flags_serde {
 /// * DispositionFlags: A set of flags available to the client trigger
 ///   specific actions on the Iridium Gateway. See [DispositionFlags] for
 ///   more details.
-pub(crate) struct Header {
+pub struct Header {
     client_msg_id: u32, // or 4 u8?
-    imei: [u8; 15],
+    #[builder(setter(into))]
+    imei: Imei,
     #[builder(default = "DispositionFlagsBuilder::default().build().unwrap()")]
     disposition_flags: DispositionFlags,
 }
 
 impl Header {
     // Import a Header from a Read trait
-    pub(super) fn from_reader<R: std::io::Read>(mut rdr: R) -> Result<Header, Error> {
+    pub(super) fn from_reader<R: Read>(mut rdr: R) -> Result<Header, Error> {
         let iei = rdr.read_u8()?;
-        assert_eq!(iei, 0x41);
+        if iei != 0x41 {
+            return Err(Error::WrongIEType("MT-Header".to_string(), 0x41, iei));
+        }
         let len = rdr.read_u16::<BigEndian>()?;
-        assert_eq!(len, 21);
+        if len != 21 {
+            return Err(Error::InvalidIELength {
+                iei,
+                length: len,
+                offset: 1,
+            });
+        }
 
         let client_msg_id = rdr.read_u32::<BigEndian>()?;
         let mut imei = [0; 15];
         rdr.read_exact(&mut imei)?;
+        let imei = Imei::from(imei);
         let disposition_flags = DispositionFlags::from_reader(rdr)?;
 
         Ok(Header {
@@ -332,20 +393,18 @@ impl Header {
     }
 
     /// client_msg_id field
-    #[allow(dead_code)]
-    fn client_msg_id(self) -> u32 {
+    pub fn client_msg_id(&self) -> u32 {
         self.client_msg_id
     }
 
     /// imei field
-    pub(crate) fn imei(&self) -> [u8; 15] {
+    pub fn imei(&self) -> Imei {
         self.imei
     }
 
     /// DispositionFlags field
-    #[allow(dead_code)]
-    fn disposition_flags(self) -> DispositionFlags {
-        self.disposition_flags
+    pub fn disposition_flags(&self) -> &DispositionFlags {
+        &self.disposition_flags
     }
 
     #[allow(dead_code)]
@@ -370,17 +429,34 @@ impl InformationElement for Header {
         21
     }
 
+    /// Fields of a Header, to annotate a hexdump
+    fn fields(&self) -> Vec<(&'static str, usize)> {
+        vec![
+            ("Client message ID", 4),
+            ("IMEI", 15),
+            ("Disposition flags", 2),
+        ]
+    }
+
     // Export a Header using a Write trait
-    fn write<W: std::io::Write>(&self, wtr: &mut W) -> Result<usize, Error> {
+    fn write<W: Write>(&self, wtr: &mut W) -> Result<usize, Error> {
         wtr.write_u8(0x41)?;
         wtr.write_u16::<BigEndian>(21)?;
         wtr.write_u32::<BigEndian>(self.client_msg_id)?;
-        wtr.write_all(&self.imei)?;
+        wtr.write_all(self.imei.as_bytes())?;
         self.disposition_flags.write(wtr)?;
         Ok(24)
     }
 }
 
+impl core::fmt::Display for Header {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        writeln!(f, "Client message ID: {}", self.client_msg_id)?;
+        writeln!(f, "IMEI: {}", self.imei)?;
+        write!(f, "Disposition flags: {}", self.disposition_flags)
+    }
+}
+
 #[cfg(test)]
 mod test_mt_header {
     use super::{DispositionFlags, Header, InformationElement};
@@ -389,7 +465,7 @@ mod test_mt_header {
     fn header_write() {
         let header = Header {
             client_msg_id: 9999,
-            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14],
+            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14].into(),
             disposition_flags: DispositionFlags {
                 flush_queue: true,
                 send_ring_alert: true,
@@ -415,7 +491,7 @@ mod test_mt_header {
     fn header_to_vec() {
         let header = Header {
             client_msg_id: 9999,
-            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14],
+            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14].into(),
             disposition_flags: DispositionFlags {
                 flush_queue: true,
                 send_ring_alert: true,
@@ -439,7 +515,7 @@ mod test_mt_header {
     fn roundtrip_to_vec_n_read() {
         let header = Header {
             client_msg_id: 9999,
-            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14],
+            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14].into(),
             disposition_flags: DispositionFlags {
                 flush_queue: true,
                 send_ring_alert: true,
@@ -463,7 +539,7 @@ mod test_header_serde {
     fn roundtrip() {
         let header = Header {
             client_msg_id: 9999,
-            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14],
+            imei: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14].into(),
             disposition_flags: DispositionFlags {
                 flush_queue: true,
                 send_ring_alert: true,
@@ -0,0 +1,188 @@
+//! Mobile Terminated Location Information
+//!
+//! The MT location information is one of the information element types that
+//! compose a mobile terminated message. It is defined by an information
+//! element identifier (IEI) with value 0x43, and is used together with the
+//! Update SSD Location disposition flag to inform the Iridium Gateway of the
+//! modem's location.
+
+use alloc::string::ToString;
+use alloc::vec::Vec;
+
+use byteorder::BigEndian;
+use log::debug;
+
+use crate::error::{Error, Result};
+use crate::io::{Read, ReadBytesExt, Write, WriteBytesExt};
+use crate::mo::Coordinate;
+use crate::InformationElement;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// Mobile Terminated Location Information
+///
+/// IEI: 0x43
+///
+/// Fixed total size of 14 bytes.
+///
+/// # Components
+///
+/// * Coordinate: Latitude and longitude, encoded in degrees and thousandths
+///   of minutes, with the same format used by the MO Location Information.
+/// * CEP radius: Circular Error Probable radius, in km, of the given
+///   location.
+///
+/// As for the MO Location Information, the coordinate is kept as received.
+pub struct Location {
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::coordinate"))]
+    coordinate: [u8; 7],
+    cep_radius: u32,
+}
+
+impl Location {
+    pub(super) fn new(coordinate: Coordinate, cep_radius: u32) -> Location {
+        Location {
+            coordinate: coordinate.encode(),
+            cep_radius,
+        }
+    }
+
+    /// Parse a Location from a Read trait
+    pub(super) fn from_reader<R: Read>(mut rdr: R) -> Result<Location> {
+        let iei = rdr.read_u8()?;
+        if iei != 0x43 {
+            debug!(
+                "Wrong IEI type for MT-Location. Expected 0x43 instead of {}",
+                &iei
+            );
+            return Err(Error::WrongIEType("MT-Location".to_string(), 0x43, iei));
+        }
+        let len = rdr.read_u16::<BigEndian>()?;
+        if len != 11 {
+            debug!("MT-Location with unexpected length: {} bytes", len);
+            return Err(Error::InvalidIELength {
+                iei,
+                length: len,
+                offset: 1,
+            });
+        }
+
+        let mut coordinate = [0u8; 7];
+        rdr.read_exact(&mut coordinate)?;
+        let cep_radius = rdr.read_u32::<BigEndian>()?;
+
+        Ok(Location {
+            coordinate,
+            cep_radius,
+        })
+    }
+
+    /// Location coordinate
+    ///
+    /// Fails if the coordinate received is not valid, see
+    /// [Coordinate::decode].
+    pub fn coordinate(&self) -> Result<Coordinate> {
+        Coordinate::decode(&self.coordinate)
+    }
+
+    /// Latitude in decimal degrees, as received even if not valid
+    pub fn latitude(&self) -> f64 {
+        Coordinate::decode_unchecked(&self.coordinate).latitude()
+    }
+
+    /// Longitude in decimal degrees, as received even if not valid
+    pub fn longitude(&self) -> f64 {
+        Coordinate::decode_unchecked(&self.coordinate).longitude()
+    }
+
+    /// Circular Error Probable (CEP) radius in km
+    pub fn cep_radius(&self) -> u32 {
+        self.cep_radius
+    }
+}
+
+impl InformationElement for Location {
+    /// MT-Location Identifier
+    fn identifier(&self) -> u8 {
+        0x43
+    }
+
+    /// Location element length
+    fn len(&self) -> u16 {
+        11
+    }
+
+    /// Fields of a Location, to annotate a hexdump
+    fn fields(&self) -> Vec<(&'static str, usize)> {
+        let mut fields = Coordinate::FIELDS.to_vec();
+        fields.push(("CEP radius", 4));
+        fields
+    }
+
+    /// Export a Location using a Write trait
+    fn write<W: Write>(&self, wtr: &mut W) -> Result<usize> {
+        wtr.write_u8(self.identifier())?;
+        wtr.write_u16::<BigEndian>(self.len())?;
+        wtr.write_all(&self.coordinate)?;
+        wtr.write_u32::<BigEndian>(self.cep_radius)?;
+        Ok(14)
+    }
+}
+
+impl core::fmt::Display for Location {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        writeln!(f, "Latitude: {:.5}°", self.latitude())?;
+        writeln!(f, "Longitude: {:.5}°", self.longitude())?;
+        write!(f, "CEP radius: {} km", self.cep_radius)
+    }
+}
+
+#[cfg(test)]
+mod test_mt_location {
+    use super::{Coordinate, InformationElement, Location};
+
+    #[test]
+    fn write() {
+        let location = Location::new(Coordinate::new(15.0, -38.0).unwrap(), 10);
+        assert_eq!(
+            location.to_vec(),
+            [0x43, 0x00, 0x0b, 0x01, 0x0f, 0x00, 0x00, 0x26, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0a]
+        );
+    }
+
+    #[test]
+    fn roundtrip_write_n_read() {
+        let buffer = [
+            0x43, 0x00, 0x0b, 0x01, 0x21, 0x28, 0x47, 0x76, 0x7f, 0x06, 0x00, 0x01, 0x00, 0x00,
+        ];
+        let location = Location::from_reader(buffer.as_slice()).unwrap();
+        assert_eq!(location.cep_radius(), 65536);
+        assert_eq!(location.to_vec(), buffer);
+    }
+
+    #[test]
+    fn read_wrong_iei() {
+        let buffer = [
+            0x03, 0x00, 0x0b, 0x01, 0x21, 0x28, 0x47, 0x76, 0x7f, 0x06, 0x00, 0x01, 0x00, 0x00,
+        ];
+        assert!(matches!(
+            Location::from_reader(buffer.as_slice()),
+            Err(crate::error::Error::WrongIEType(_, 0x43, 0x03))
+        ));
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test_location_serde {
+    use super::{Coordinate, Location};
+
+    #[test]
+    fn roundtrip() {
+        let location = Location::new(Coordinate::new(-12.5, 145.25).unwrap(), 3);
+        let json = serde_json::to_string(&location).unwrap();
+
+        let roundtrip: Location = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(location, roundtrip);
+    }
+}
@@ -7,6 +7,7 @@
 //! * 0x43 Lat/Lon Location Information IEI
 //! * 0x44 Confirmation Message IEI
 //! * 0x45 LAC/Cell ID Location Informatio IEI
+//! * 0x46 Priority IEI
 //!
 //! ## Example of an MT Message
 //!
@@ -23,24 +24,46 @@
 
 mod confirmation;
 mod header;
+mod location;
 mod payload;
+mod priority;
 
-use std::io::Read;
+use alloc::string::{String, ToString};
+use alloc::vec;
+use alloc::vec::Vec;
 
-use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use byteorder::BigEndian;
+use log::debug;
 
+use crate::diff::ElementFields;
 use crate::error::{Error, Result};
-use crate::InformationElement;
-use confirmation::Confirmation;
-use header::{Header, HeaderBuilder};
+use crate::hexdump::HexDump;
+use crate::io::{Read, ReadBytesExt, Write, WriteBytesExt};
+use crate::mo::Coordinate;
+use crate::modem::check_payload;
+use crate::{Imei, InformationElement, Modem};
+pub use confirmation::{Confirmation, ConfirmationBuilder, MessageStatus};
+pub use header::{DispositionFlags, Header};
+use header::{DispositionFlagsBuilder, HeaderBuilder};
+pub use location::Location;
+pub(crate) use payload::MAX_PAYLOAD_LEN;
 use payload::{Payload, PayloadBuilder};
+use priority::{Priority, PriorityBuilder};
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 enum InformationElementType {
     H(Header),
     P(Payload),
     C(Confirmation),
+    L(Location),
+    PR(Priority),
+    /// An Information Element not known by this library, kept as received
+    Unknown {
+        iei: u8,
+        #[cfg_attr(feature = "serde", serde(with = "crate::encoding::element"))]
+        bytes: Vec<u8>,
+    },
 }
 
 impl InformationElement for InformationElementType {
@@ -49,6 +72,9 @@ impl InformationElement for InformationElementType {
             InformationElementType::H(element) => element.identifier(),
             InformationElementType::P(element) => element.identifier(),
             InformationElementType::C(element) => element.identifier(),
+            InformationElementType::L(element) => element.identifier(),
+            InformationElementType::PR(element) => element.identifier(),
+            InformationElementType::Unknown { iei, .. } => *iei,
         }
     }
 
@@ -57,40 +83,85 @@ impl InformationElement for InformationElementType {
             InformationElementType::H(element) => element.len(),
             InformationElementType::P(element) => element.len(),
             InformationElementType::C(element) => element.len(),
+            InformationElementType::L(element) => element.len(),
+            InformationElementType::PR(element) => element.len(),
+            InformationElementType::Unknown { bytes, .. } => {
+                // Bounded by parsing and deserialization
+                bytes.len().try_into().expect("Unknown element too large")
+            }
         }
     }
 
-    fn write<W: std::io::Write>(&self, wtr: &mut W) -> Result<usize> {
+    fn write<W: Write>(&self, wtr: &mut W) -> Result<usize> {
         match self {
             InformationElementType::H(element) => element.write(wtr),
             InformationElementType::P(element) => element.write(wtr),
             InformationElementType::C(element) => element.write(wtr),
+            InformationElementType::L(element) => element.write(wtr),
+            InformationElementType::PR(element) => element.write(wtr),
+            InformationElementType::Unknown { iei, bytes } => {
+                wtr.write_u8(*iei)?;
+                wtr.write_u16::<BigEndian>(self.len())?;
+                wtr.write_all(bytes)?;
+                Ok(3 + bytes.len())
+            }
+        }
+    }
+
+    fn fields(&self) -> Vec<(&'static str, usize)> {
+        match self {
+            InformationElementType::H(element) => element.fields(),
+            InformationElementType::P(element) => element.fields(),
+            InformationElementType::C(element) => element.fields(),
+            InformationElementType::L(element) => element.fields(),
+            InformationElementType::PR(element) => element.fields(),
+            InformationElementType::Unknown { .. } => {
+                vec![("Contents", usize::from(self.len()))]
+            }
         }
     }
 }
 
 impl InformationElementType {
+    /// Name of the Information Element
+    fn name(&self) -> &'static str {
+        match self {
+            InformationElementType::H(_) => "MT Header",
+            InformationElementType::P(_) => "MT Payload",
+            InformationElementType::C(_) => "MT Confirmation",
+            InformationElementType::L(_) => "MT Location",
+            InformationElementType::PR(_) => "MT Message Priority",
+            InformationElementType::Unknown { .. } => "Unknown",
+        }
+    }
+
     /// Parse a InformationElementType from a Read trait
-    pub(super) fn from_reader<R: std::io::Read>(mut rdr: R) -> Result<Self> {
+    pub(super) fn from_reader<R: Read>(mut rdr: R) -> Result<Self> {
         let iei = rdr.read_u8()?;
         let buffer = [iei; 1];
         let buffer = buffer.chain(rdr);
         let element = match iei {
-            0x41 => {
-                let header = Header::from_reader(buffer).unwrap();
-                InformationElementType::H(header)
-            }
-            0x42 => {
-                let payload = Payload::from_reader(buffer).unwrap();
-                InformationElementType::P(payload)
-            }
-            0x44 => {
-                let confirmation = Confirmation::from_reader(buffer).unwrap();
-                InformationElementType::C(confirmation)
-            }
-            _ => return Err(Error::Undefined),
+            0x41 => Header::from_reader(buffer).map(InformationElementType::H),
+            0x42 => Payload::from_reader(buffer).map(InformationElementType::P),
+            0x43 => Location::from_reader(buffer).map(InformationElementType::L),
+            0x44 => Confirmation::from_reader(buffer).map(InformationElementType::C),
+            0x46 => Priority::from_reader(buffer).map(InformationElementType::PR),
+            _ => InformationElementType::unknown_from_reader(buffer),
         };
-        Ok(element)
+        element.map_err(|e| e.truncated(iei))
+    }
+
+    /// Parse an Information Element not known by this library
+    ///
+    /// The element is kept as received, so that it can be written back
+    /// unchanged.
+    fn unknown_from_reader<R: Read>(mut rdr: R) -> Result<Self> {
+        let iei = rdr.read_u8()?;
+        debug!("Unknown IEI: 0x{:02x}", iei);
+        let n = rdr.read_u16::<BigEndian>()?;
+        let mut bytes = vec![0; usize::from(n)];
+        rdr.read_exact(&mut bytes)?;
+        Ok(InformationElementType::Unknown { iei, bytes })
     }
 }
 
@@ -146,6 +217,19 @@ mod test_mt_information_element_serde {
         assert_eq!(ie, roundtrip);
     }
 
+    #[test]
+    fn unknown_roundtrip() {
+        let ie = InformationElementType::Unknown {
+            iei: 0x45,
+            bytes: vec![0x12, 0x34],
+        };
+        let json = serde_json::to_string(&ie).unwrap();
+
+        let roundtrip: InformationElementType = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(ie, roundtrip);
+    }
+
     #[test]
     fn header_roundtrip() {
         let header = Header::builder()
@@ -174,12 +258,24 @@ impl From<Header> for InformationElementType {
     }
 }
 
+impl From<Location> for InformationElementType {
+    fn from(location: Location) -> Self {
+        InformationElementType::L(location)
+    }
+}
+
 impl From<Payload> for InformationElementType {
     fn from(payload: Payload) -> Self {
         InformationElementType::P(payload)
     }
 }
 
+impl From<Priority> for InformationElementType {
+    fn from(priority: Priority) -> Self {
+        InformationElementType::PR(priority)
+    }
+}
+
 #[cfg(test)]
 mod test_mt_information_element_from {
     use crate::mt::{Header, InformationElement, InformationElementType, Payload};
@@ -203,9 +299,43 @@ mod test_mt_information_element_from {
     }
 }
 
+impl core::fmt::Display for InformationElementType {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "IEI 0x{:02x} ", self.identifier())?;
+        let content: &dyn core::fmt::Display = match self {
+            InformationElementType::H(h) => h,
+            InformationElementType::P(p) => p,
+            InformationElementType::C(c) => c,
+            InformationElementType::L(l) => l,
+            InformationElementType::PR(p) => p,
+            InformationElementType::Unknown { bytes, .. } => {
+                write!(f, "{} ({} bytes): ", self.name(), self.len())?;
+                return crate::describe::write_preview(f, bytes);
+            }
+        };
+        write!(f, "{} ({} bytes)", self.name(), self.len())?;
+        crate::describe::write_indented(f, content, "  ")
+    }
+}
+
+/// Human-readable report of the Message, one Information Element at a time
+impl core::fmt::Display for MTMessage {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "MT Message ({} bytes)", self.total_size())?;
+        for e in &self.elements {
+            crate::describe::write_indented(f, e, "  ")?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct MTMessage {
+    #[cfg_attr(
+        feature = "serde",
+        serde(deserialize_with = "crate::encoding::deserialize_elements")
+    )]
     elements: Vec<InformationElementType>,
 }
 
@@ -220,12 +350,10 @@ impl MTMessage {
 
     /// Overall Message Length
     fn len(&self) -> u16 {
-        self.elements
-            .iter()
-            .map(|e| e.total_size())
-            .sum::<usize>()
+        // Bounded by the builder and deserialization
+        (self.total_size() - 3)
             .try_into()
-            .unwrap()
+            .expect("Message too large")
     }
 
     fn total_size(&self) -> usize {
@@ -233,7 +361,7 @@ impl MTMessage {
     }
 
     // Write the full message
-    fn write<W: std::io::Write>(&self, wtr: &mut W) -> Result<usize> {
+    fn write<W: Write>(&self, wtr: &mut W) -> Result<usize> {
         // Protocol version
         wtr.write_u8(1)?;
         // Message total length
@@ -248,28 +376,97 @@ impl MTMessage {
 
     /// Export MT-Message into a vector of u8
     pub fn to_vec(&self) -> Vec<u8> {
+        self.try_to_vec()
+            .expect("Failed to write Information Element to a vec.")
+    }
+
+    /// Export into a vector of u8, failing if anything can't be encoded
+    pub(crate) fn try_to_vec(&self) -> Result<Vec<u8>> {
         let mut buffer: Vec<u8> = Vec::new();
-        self.write(&mut buffer)
-            .expect("Failed to write Information Element to a vec.");
-        buffer
+        self.write(&mut buffer)?;
+        Ok(buffer)
+    }
+
+    /// Annotated hexdump of the encoded message
+    ///
+    /// Each byte range is labelled with what it is, such as the Information
+    /// Element Identifiers, their lengths, and each of their fields. Fails
+    /// if the message can't be encoded.
+    pub fn hexdump(&self) -> Result<String> {
+        let mut dump = HexDump::new(self.try_to_vec()?);
+        dump.message_header(self.len());
+        for e in &self.elements {
+            dump.element(e.name(), e);
+        }
+        Ok(dump.finish())
+    }
+
+    /// Information Elements split in their fields, to be compared
+    pub(crate) fn element_fields(&self) -> Result<Vec<ElementFields>> {
+        self.elements
+            .iter()
+            .map(|e| ElementFields::new(e.name(), e))
+            .collect()
+    }
+
+    /// Encode into a slice of bytes, returning the number of bytes written
+    pub fn encode(&self, buffer: &mut [u8]) -> Result<usize> {
+        let required = self.total_size();
+        if buffer.len() < required {
+            return Err(Error::BufferTooSmall {
+                required,
+                available: buffer.len(),
+            });
+        }
+        let mut wtr = &mut buffer[..required];
+        self.write(&mut wtr)
+    }
+
+    /// Parse an MTMessage from a slice of bytes
+    pub fn from_bytes(buffer: &[u8]) -> Result<Self> {
+        Self::read(buffer)
+    }
+
+    /// Parse an MTMessage from a reader
+    #[cfg(feature = "std")]
+    pub fn from_reader<R: std::io::Read>(rdr: R) -> Result<Self> {
+        Self::read(rdr)
     }
 
     /// Parse bytes from a buffer to compose an MTMessage
-    pub fn from_reader<R: std::io::Read>(mut rdr: R) -> Result<Self> {
+    pub(crate) fn read<R: Read>(mut rdr: R) -> Result<Self> {
         // Protocol version
         let version = rdr.read_u8()?;
         // Expects version 1
-        assert_eq!(version, 1);
+        if version != 1 {
+            return Err(Error::UnsupportedVersion { version, offset: 0 });
+        }
         // Message total length
-        let length = rdr.read_u16::<BigEndian>().expect("Failed to read length") as usize;
+        let length = usize::from(rdr.read_u16::<BigEndian>()?);
 
         let mut msg = Self::new();
         let mut n = 0;
         while n < length {
-            let element = InformationElementType::from_reader(&mut rdr)?;
+            let element = match InformationElementType::from_reader(&mut rdr) {
+                Ok(element) => element,
+                // Input ended before the next IEI
+                Err(e) if e.is_eof() => {
+                    return Err(Error::MessageLengthMismatch {
+                        declared: length,
+                        actual: n,
+                    })
+                }
+                Err(e) => return Err(e.shift(3 + n)),
+            };
             n += element.total_size();
             msg.push(element);
         }
+        if n != length {
+            return Err(Error::MessageLengthMismatch {
+                declared: length,
+                actual: n,
+            });
+        }
 
         Ok(msg)
     }
@@ -280,13 +477,38 @@ impl MTMessage {
 
     /// Appends an element to the back of an MT-Message
     ///
-    /// This should be a good place to check for duplicates, i.e. try to insert
-    /// a second header for instance.
+    /// Nothing is checked here, a received message might not be compliant.
+    /// See [crate::Message::validate] for duplicates and other violations.
     fn push(&mut self, element: InformationElementType) {
         self.elements.push(element);
     }
 
-    fn confirmation(&self) -> Option<&Confirmation> {
+    /// Compose the confirmation a gateway sends in reply to this MT message
+    ///
+    /// The client message ID and the IMEI are copied from the MT Header.
+    /// The `id_reference` is the auto ID reference assigned by the gateway,
+    /// which is replaced by zero if the message status is an error, as done
+    /// by the Iridium Gateway. Fails if this message has no MT Header.
+    pub fn confirm(&self, message_status: MessageStatus, id_reference: u32) -> Result<MTMessage> {
+        let header = self
+            .header()
+            .ok_or_else(|| Error::InvalidMessage("Missing MT Header".to_string()))?;
+        let id_reference = if message_status.is_successful() {
+            id_reference
+        } else {
+            0
+        };
+        let confirmation = Confirmation::builder()
+            .client_msg_id(header.client_msg_id())
+            .imei(header.imei())
+            .id_reference(id_reference)
+            .message_status(message_status)
+            .build()?;
+        Ok(confirmation.into())
+    }
+
+    /// Confirmation element, if this is an MT confirmation message
+    pub fn confirmation(&self) -> Option<&Confirmation> {
         self.elements
             .iter()
             .find(|elem| matches!(elem, InformationElementType::C(_)))
@@ -303,7 +525,8 @@ impl MTMessage {
         self.confirmation().map(|v| v.message_status().to_string())
     }
 
-    fn header(&self) -> Option<&Header> {
+    /// Header element
+    pub fn header(&self) -> Option<&Header> {
         self.elements
             .iter()
             .find(|elem| matches!(elem, InformationElementType::H(_)))
@@ -316,22 +539,132 @@ impl MTMessage {
             })
     }
 
-    pub fn imei(&self) -> Option<[u8; 15]> {
+    pub fn imei(&self) -> Option<Imei> {
         self.elements.iter().find_map(|elem| match elem {
             InformationElementType::H(h) => Some(h.imei()),
             InformationElementType::C(c) => Some(c.imei()),
             _ => None,
         })
     }
+
+    /// Disposition flags, as defined in the Header
+    pub fn disposition_flags(&self) -> Option<&DispositionFlags> {
+        self.header().map(|h| h.disposition_flags())
+    }
+
+    /// Payload contents, if available
+    pub fn payload(&self) -> Option<&[u8]> {
+        self.elements.iter().find_map(|elem| match elem {
+            InformationElementType::P(p) => Some(p.payload()),
+            _ => None,
+        })
+    }
+
+    /// Priority level, from 1 (highest) to 5 (lowest), if defined
+    pub fn priority(&self) -> Option<u16> {
+        self.elements.iter().find_map(|elem| match elem {
+            InformationElementType::PR(p) => Some(p.level()),
+            _ => None,
+        })
+    }
+
+    /// Location to update the SSD with, if defined
+    pub fn location(&self) -> Option<&Location> {
+        self.elements.iter().find_map(|elem| match elem {
+            InformationElementType::L(l) => Some(l),
+            _ => None,
+        })
+    }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod test_mt_message {
-    use super::MTMessage;
+    use super::{Error, InformationElementType, MTMessage};
+
+    #[test]
+    fn unsupported_version() {
+        let buffer = [0x00, 0x00, 0x05, 0x46, 0x00, 0x02, 0x00, 0x01];
+        assert!(matches!(
+            MTMessage::from_reader(buffer.as_slice()),
+            Err(Error::UnsupportedVersion {
+                version: 0,
+                offset: 0
+            })
+        ));
+    }
+
+    #[test]
+    fn unknown_iei() {
+        // An LAC/Cell ID Location Information element (0x45)
+        let buffer = [
+            0x01, 0x00, 0x0a, 0x46, 0x00, 0x02, 0x00, 0x01, 0x45, 0x00, 0x02, 0x12, 0x34,
+        ];
+        let msg = MTMessage::from_reader(buffer.as_slice()).unwrap();
+        assert_eq!(msg.priority(), Some(1));
+        assert!(matches!(
+            &msg.elements[1],
+            InformationElementType::Unknown { iei: 0x45, bytes } if bytes == &[0x12, 0x34]
+        ));
+        assert_eq!(msg.to_vec(), buffer);
+    }
+
+    #[test]
+    fn invalid_header_length() {
+        let buffer = [
+            0x01, 0x00, 0x18, 0x41, 0x00, 0x16, 0x00, 0x00, 0x27, 0x0f, 0x00, 0x01, 0x02, 0x03,
+            0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x00, 0x3b,
+        ];
+        assert!(matches!(
+            MTMessage::from_reader(buffer.as_slice()),
+            Err(Error::InvalidIELength {
+                iei: 0x41,
+                length: 22,
+                offset: 4
+            })
+        ));
+    }
+
+    #[test]
+    fn truncated_payload() {
+        let buffer = [
+            0x01, 0x00, 0x0d, 0x46, 0x00, 0x02, 0x00, 0x01, 0x42, 0x00, 0x05, 0x48, 0x65,
+        ];
+        assert!(matches!(
+            MTMessage::from_reader(buffer.as_slice()),
+            Err(Error::TruncatedElement {
+                iei: 0x42,
+                offset: 8
+            })
+        ));
+    }
 
     #[test]
     fn to_vec() {}
 
+    #[test]
+    fn header_n_payload() {
+        let buffer = [
+            0x01, 0x00, 0x1d, 0x41, 0x00, 0x15, 0x00, 0x00, 0x27, 0x0f, 0x00, 0x01, 0x02, 0x03,
+            0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x00, 0x11, 0x42,
+            0x00, 0x02, 0x68, 0x69,
+        ];
+        let msg = MTMessage::from_reader(buffer.as_slice()).unwrap();
+        let header = msg.header().unwrap();
+        assert_eq!(header.client_msg_id(), 9999);
+        assert_eq!(
+            header.imei(),
+            [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14]
+        );
+        let flags = msg.disposition_flags().unwrap();
+        assert!(flags.flush_queue());
+        assert!(!flags.send_ring_alert());
+        assert!(!flags.update_location());
+        assert!(flags.high_priority());
+        assert!(!flags.assign_mtmsn());
+        assert_eq!(msg.payload(), Some("hi".as_bytes()));
+        assert!(msg.confirmation().is_none());
+    }
+
     #[test]
     // Could improve this test with some checks on the output of from_reader()
     fn confirmation_from_reader() {
@@ -341,20 +674,111 @@ mod test_mt_message {
             0x00, 0x00, 0x2a,
         ];
 
-        MTMessage::from_reader(&buffer[..]).unwrap();
+        let msg = MTMessage::from_reader(&buffer[..]).unwrap();
+        let confirmation = msg.confirmation().unwrap();
+        assert_eq!(confirmation.client_msg_id(), 1111);
+        assert_eq!(confirmation.id_reference(), 0);
+        assert!(confirmation.message_status().is_successful());
+    }
+}
+
+/// An MT confirmation message, containing only the given Confirmation
+impl From<Confirmation> for MTMessage {
+    fn from(confirmation: Confirmation) -> Self {
+        let mut msg = MTMessage::new();
+        msg.push(confirmation.into());
+        msg
+    }
+}
+
+#[cfg(test)]
+mod test_mt_confirm {
+    use super::{Confirmation, Error, MTMessage, MessageStatus};
+
+    fn request() -> MTMessage {
+        MTMessage::builder()
+            .client_msg_id(9999)
+            .imei("300234010753376".parse().unwrap())
+            .payload(vec![0x42])
+            .build()
+            .unwrap()
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn confirm() {
+        let msg = request()
+            .confirm(MessageStatus::SuccessfulQueueOrder(3), 1234)
+            .unwrap();
+        let confirmation = msg.confirmation().unwrap();
+        assert_eq!(confirmation.client_msg_id(), 9999);
+        assert_eq!(confirmation.imei(), *b"300234010753376");
+        assert_eq!(confirmation.id_reference(), 1234);
+        assert_eq!(
+            confirmation.message_status(),
+            &MessageStatus::SuccessfulQueueOrder(3)
+        );
+        assert!(msg.header().is_none());
+        assert!(msg.payload().is_none());
+
+        let roundtrip = MTMessage::from_reader(msg.to_vec().as_slice()).unwrap();
+        assert_eq!(roundtrip.to_vec(), msg.to_vec());
+    }
+
+    #[test]
+    // No ID reference is assigned on errors
+    fn confirm_error() {
+        let msg = request().confirm(MessageStatus::MTQueueFull, 1234).unwrap();
+        let confirmation = msg.confirmation().unwrap();
+        assert_eq!(confirmation.id_reference(), 0);
+        assert_eq!(confirmation.message_status(), &MessageStatus::MTQueueFull);
+    }
+
+    #[test]
+    fn confirm_missing_header() {
+        let msg = crate::sample();
+        let crate::Message::MT(msg) = msg else {
+            unreachable!()
+        };
+        assert!(matches!(
+            msg.confirm(MessageStatus::ProtocolViolation, 0),
+            Err(Error::InvalidMessage(_))
+        ));
+    }
+
+    #[test]
+    fn from_confirmation() {
+        let confirmation = Confirmation::builder()
+            .client_msg_id(9999)
+            .imei([0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14])
+            .id_reference(0)
+            .message_status(MessageStatus::InvalidIMEI)
+            .build()
+            .unwrap();
+        let msg = MTMessage::from(confirmation);
+        assert_eq!(msg.to_vec().len(), 31);
+        assert!(msg.confirmation().is_some());
     }
 }
 
 pub struct MTMessageBuilder {
     header: HeaderBuilder,
-    payload: PayloadBuilder,
+    disposition_flags: DispositionFlagsBuilder,
+    payload: Option<PayloadBuilder>,
+    priority: Option<PriorityBuilder>,
+    location: Option<(f64, f64, u32)>,
+    modem: Option<Modem>,
 }
 
 impl MTMessageBuilder {
     fn default() -> MTMessageBuilder {
         MTMessageBuilder {
             header: HeaderBuilder::default(),
-            payload: PayloadBuilder::default(),
+            disposition_flags: DispositionFlagsBuilder::default(),
+            payload: None,
+            priority: None,
+            location: None,
+            modem: None,
         }
     }
 
@@ -363,42 +787,275 @@ impl MTMessageBuilder {
         self
     }
 
-    pub fn imei(mut self, imei: [u8; 15]) -> Self {
+    pub fn imei(mut self, imei: Imei) -> Self {
         self.header = self.header.imei(imei);
         self
     }
 
+    /// Delete all MT payloads in the SSD's MT queue
+    pub fn flush_queue(mut self, value: bool) -> Self {
+        self.disposition_flags = self.disposition_flags.flush_queue(value);
+        self
+    }
+
+    /// Send a ring alert with no associated MT payload
+    pub fn send_ring_alert(mut self, value: bool) -> Self {
+        self.disposition_flags = self.disposition_flags.send_ring_alert(value);
+        self
+    }
+
+    /// Update the SSD location with the given [MTMessageBuilder::location]
+    pub fn update_location(mut self, value: bool) -> Self {
+        self.disposition_flags = self.disposition_flags.update_location(value);
+        self
+    }
+
+    /// Place the payload in front of the SSD's MT queue
+    pub fn high_priority(mut self, value: bool) -> Self {
+        self.disposition_flags = self.disposition_flags.high_priority(value);
+        self
+    }
+
+    /// Use the client message ID as the MTMSN
+    pub fn assign_mtmsn(mut self, value: bool) -> Self {
+        self.disposition_flags = self.disposition_flags.assign_mtmsn(value);
+        self
+    }
+
     pub fn payload(mut self, payload: Vec<u8>) -> Self {
-        self.payload = self.payload.payload(payload);
+        self.payload = Some(PayloadBuilder::default().payload(payload));
+        self
+    }
+
+    /// Priority level, from 1 (highest) to 5 (lowest)
+    pub fn priority(mut self, level: u16) -> Self {
+        self.priority = Some(PriorityBuilder::default().level(level));
+        self
+    }
+
+    /// Location, in decimal degrees, and its CEP radius in km
+    ///
+    /// Used together with the Update SSD Location disposition flag.
+    pub fn location(mut self, latitude: f64, longitude: f64, cep_radius: u32) -> Self {
+        self.location = Some((latitude, longitude, cep_radius));
+        self
+    }
+
+    /// Modem receiving the message, to check the payload against its limit
+    ///
+    /// Otherwise, an oversized payload is only noticed with the gateway's
+    /// confirmation or a failed delivery.
+    pub fn modem(mut self, modem: Modem) -> Self {
+        self.modem = Some(modem);
         self
     }
 
-    pub fn build(self) -> MTMessage {
+    /// Check for combinations that the Iridium Gateway would reject
+    ///
+    /// Those would be answered with a Protocol Violation confirmation, thus
+    /// it is better to fail before sending it.
+    fn validate(&self, header: &Header) -> Result<()> {
+        let flags = header.disposition_flags();
+        let violation = |reason: &str| Err(Error::InvalidMessage(reason.to_string()));
+
+        if self.payload.is_some() {
+            if flags.flush_queue() {
+                return violation("Flush MT Queue can't carry a payload");
+            }
+            if flags.send_ring_alert() {
+                return violation("Send Ring Alert can't carry a payload");
+            }
+        } else if !(flags.flush_queue() || flags.send_ring_alert() || flags.update_location()) {
+            return violation("Missing payload");
+        }
+
+        match (flags.update_location(), self.location.is_some()) {
+            (true, false) => return violation("Update SSD Location requires a location"),
+            (false, true) => return violation("Location requires Update SSD Location"),
+            _ => (),
+        }
+
+        // The client message ID is used as the MTMSN, which is 2 bytes
+        if flags.assign_mtmsn() && !(1..=u32::from(u16::MAX)).contains(&header.client_msg_id()) {
+            return violation("Assign MTMSN requires a client message ID from 1 to 65535");
+        }
+
+        Ok(())
+    }
+
+    pub fn build(mut self) -> Result<MTMessage> {
+        let header = core::mem::take(&mut self.header)
+            .disposition_flags(core::mem::take(&mut self.disposition_flags).build()?)
+            .build()?;
+        self.validate(&header)?;
+
         let mut msg = MTMessage::new();
-        msg.push(self.header.build().unwrap().into());
-        msg.push(self.payload.build().unwrap().into());
-        msg
+        msg.push(header.into());
+        if let Some(payload) = self.payload {
+            let payload = payload.build()?;
+            if let Some(modem) = self.modem {
+                check_payload(payload.payload().len(), modem.max_mt_payload())?;
+            }
+            msg.push(payload.into());
+        }
+        if let Some(priority) = self.priority {
+            msg.push(priority.build()?.into());
+        }
+        if let Some((latitude, longitude, cep_radius)) = self.location {
+            let coordinate = Coordinate::new(latitude, longitude)?;
+            msg.push(Location::new(coordinate, cep_radius).into());
+        }
+        Ok(msg)
     }
 }
 
 #[cfg(test)]
 mod test_mt_message_builder {
-    use crate::mt::MTMessageBuilder;
+    use crate::mt::{MTMessage, MTMessageBuilder};
+    use crate::Error;
+
+    fn builder() -> MTMessageBuilder {
+        MTMessage::builder()
+            .client_msg_id(9999)
+            .imei("300234010753376".parse().unwrap())
+    }
+
+    #[test]
+    fn build_for_modem() {
+        use crate::Modem;
+
+        let msg = builder()
+            .payload(vec![0x42; 270])
+            .modem(Modem::Iridium9603)
+            .build();
+        assert!(msg.is_ok());
+
+        let msg = builder()
+            .payload(vec![0x42; 271])
+            .modem(Modem::Iridium9603)
+            .build();
+        assert!(matches!(
+            msg,
+            Err(Error::PayloadOversized {
+                length: 271,
+                limit: 270
+            })
+        ));
+
+        // Beyond the 9603, but within the gateway limit
+        let msg = builder()
+            .payload(vec![0x42; 1890])
+            .modem(Modem::Iridium9523)
+            .build();
+        assert!(msg.is_ok());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn build_with_priority_n_location() {
+        let msg = builder()
+            .payload(vec![0x42])
+            .priority(2)
+            .update_location(true)
+            .location(15.0, -38.0, 10)
+            .build()
+            .unwrap();
+        assert_eq!(msg.priority(), Some(2));
+        assert_eq!(msg.location().unwrap().cep_radius(), 10);
+        assert!(msg.disposition_flags().unwrap().update_location());
+
+        let roundtrip = MTMessage::from_reader(msg.to_vec().as_slice()).unwrap();
+        assert_eq!(roundtrip.priority(), Some(2));
+        assert_eq!(roundtrip.location().unwrap().latitude(), 15.0);
+        assert_eq!(roundtrip.location().unwrap().longitude(), -38.0);
+        assert_eq!(roundtrip.to_vec(), msg.to_vec());
+    }
 
     #[test]
     fn build() {
-        let msg = MTMessageBuilder::default()
+        let msg = builder().payload(vec![0x42]).build().unwrap();
+        assert_eq!(msg.payload(), Some([0x42].as_slice()));
+        assert!(!msg.disposition_flags().unwrap().flush_queue());
+        assert!(crate::Message::MT(msg).validate().is_empty());
+    }
+
+    #[test]
+    // Rejected by the specification, thus by validation as well
+    fn build_empty_payload() {
+        let msg = builder().payload(vec![]).build();
+        assert!(matches!(msg, Err(Error::InvalidMessage(_))));
+    }
+
+    #[test]
+    fn build_missing_imei() {
+        let msg = MTMessage::builder()
             .client_msg_id(9999)
-            .imei([1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 1, 2, 3, 4, 5])
-            .payload(vec![])
+            .payload(vec![0x42])
+            .build();
+        assert!(matches!(msg, Err(Error::UninitializedFieldError(_))));
+    }
+
+    #[test]
+    fn build_oversized_payload() {
+        let msg = builder().payload(vec![0x42; 1891]).build();
+        assert!(msg.is_err());
+    }
+
+    #[test]
+    fn build_missing_payload() {
+        let msg = builder().high_priority(true).build();
+        assert!(matches!(msg, Err(Error::InvalidMessage(_))));
+    }
+
+    #[test]
+    fn build_ring_alert() {
+        let msg = builder().send_ring_alert(true).build().unwrap();
+        assert!(msg.payload().is_none());
+        assert!(msg.disposition_flags().unwrap().send_ring_alert());
+
+        let msg = builder().send_ring_alert(true).payload(vec![0x42]).build();
+        assert!(matches!(msg, Err(Error::InvalidMessage(_))));
+    }
+
+    #[test]
+    fn build_flush_queue() {
+        let msg = builder().flush_queue(true).build().unwrap();
+        assert!(msg.payload().is_none());
+        assert!(msg.disposition_flags().unwrap().flush_queue());
+
+        let msg = builder().flush_queue(true).payload(vec![0x42]).build();
+        assert!(matches!(msg, Err(Error::InvalidMessage(_))));
+    }
+
+    #[test]
+    fn build_update_location_without_location() {
+        let msg = builder().update_location(true).build();
+        assert!(matches!(msg, Err(Error::InvalidMessage(_))));
+
+        let msg = builder()
+            .payload(vec![0x42])
+            .location(15.0, -38.0, 10)
             .build();
-        dbg!(msg);
-
-        /*
-            //builder.header.set_client_msg_id(9999);
-            // let msg = msg.build();
-            dbg!(msg.build());
-            //assert!(false)
-        */
+        assert!(matches!(msg, Err(Error::InvalidMessage(_))));
+    }
+
+    #[test]
+    fn build_assign_mtmsn() {
+        let msg = builder()
+            .client_msg_id(123)
+            .assign_mtmsn(true)
+            .payload(vec![0x42])
+            .build()
+            .unwrap();
+        assert!(msg.disposition_flags().unwrap().assign_mtmsn());
+
+        for client_msg_id in [0, 65536] {
+            let msg = builder()
+                .client_msg_id(client_msg_id)
+                .assign_mtmsn(true)
+                .payload(vec![0x42])
+                .build();
+            assert!(matches!(msg, Err(Error::InvalidMessage(_))));
+        }
     }
 }
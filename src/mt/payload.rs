@@ -1,18 +1,23 @@
 //! Mobile Terminated - Payload
 //!
 
-use byteorder::{BigEndian, ReadBytesExt, WriteBytesExt};
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use byteorder::BigEndian;
 use derive_builder::Builder;
 use log::debug;
 
 use crate::error::{Error, Result};
+use crate::io::{Read, ReadBytesExt, Write, WriteBytesExt};
 use crate::InformationElement;
 
 /// Maximum accepted payload length defined by the Direct-IP protocol
-const MAX_PAYLOAD_LEN: usize = 1890;
+pub(crate) const MAX_PAYLOAD_LEN: usize = 1890;
 
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
-#[derive(Builder, Debug, PartialEq)]
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Hash)]
 #[builder(
     pattern = "owned",
     build_fn(error = "crate::error::Error", validate = "Self::validate")
@@ -22,7 +27,7 @@ const MAX_PAYLOAD_LEN: usize = 1890;
 /// Although length is a 2-bytes, valid range is 1-1890
 pub(super) struct Payload {
     #[builder(setter(into))]
-    #[cfg_attr(feature = "serde", serde(with = "serde_bytes"))]
+    #[cfg_attr(feature = "serde", serde(with = "crate::encoding::mt_payload"))]
     payload: Vec<u8>,
 }
 
@@ -33,13 +38,21 @@ impl InformationElement for Payload {
     }
 
     fn len(&self) -> u16 {
+        // Bounded by the builder and deserialization
         self.payload.len().try_into().expect("Payload too large")
     }
 
-    fn write<W: std::io::Write>(&self, wtr: &mut W) -> Result<usize> {
+    fn fields(&self) -> Vec<(&'static str, usize)> {
+        vec![("Payload", self.payload.len())]
+    }
+
+    fn write<W: Write>(&self, wtr: &mut W) -> Result<usize> {
         if usize::from(self.len()) > MAX_PAYLOAD_LEN {
             debug!("MT-Payload oversized, {} bytes", self.len());
-            return Err(Error::Undefined);
+            return Err(Error::PayloadOversized {
+                length: self.payload.len(),
+                limit: MAX_PAYLOAD_LEN,
+            });
         }
 
         wtr.write_u8(0x42)?;
@@ -50,7 +63,7 @@ impl InformationElement for Payload {
 }
 
 impl Payload {
-    pub(super) fn from_reader<R: std::io::Read>(mut rdr: R) -> Result<Payload> {
+    pub(super) fn from_reader<R: Read>(mut rdr: R) -> Result<Payload> {
         let iei = rdr.read_u8()?;
         if iei != 0x42 {
             debug!(
@@ -59,22 +72,29 @@ impl Payload {
             );
             return Err(Error::WrongIEType("MT-Payload".to_string(), 0x42, iei));
         }
-        let n = rdr.read_u16::<BigEndian>().unwrap().into();
+        let length = rdr.read_u16::<BigEndian>()?;
+        let n = usize::from(length);
         if n == 0 {
             Ok(Payload { payload: vec![] })
         } else if n > MAX_PAYLOAD_LEN {
             debug!("MT-Payload expected to be over-sized: {} bytes", n);
-            Err(Error::Undefined)
+            Err(Error::InvalidIELength {
+                iei,
+                length,
+                offset: 1,
+            })
         } else {
             let mut payload = vec![0; n];
             rdr.read_exact(&mut payload)?;
-            if payload.len() > n {
-                return Err(Error::Undefined);
-            }
             Ok(Payload { payload })
         }
     }
 
+    /// Payload contents
+    pub(super) fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
     #[allow(dead_code)]
     pub(crate) fn builder() -> PayloadBuilder {
         PayloadBuilder::default()
@@ -116,11 +136,10 @@ mod test_mt_payload {
     }
 }
 
-impl std::fmt::Display for Payload {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "Payload Element")?;
-        write!(f, "  len {}", self.len())?;
-        write!(f, "  {:02X?}", self.payload)
+impl core::fmt::Display for Payload {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Payload: ")?;
+        crate::describe::write_preview(f, &self.payload)
     }
 }
 
@@ -145,9 +164,16 @@ mod test_payload_serde {
 impl PayloadBuilder {
     fn validate(&self) -> Result<()> {
         if let Some(ref payload) = self.payload {
+            // At least one byte, as required by the specification
+            if payload.is_empty() {
+                return Err(Error::InvalidMessage("Empty payload".to_string()));
+            }
             if payload.len() > MAX_PAYLOAD_LEN {
-                dbg!(&payload);
-                return Err(Error::Undefined);
+                debug!("Payload too long: {} bytes", payload.len());
+                return Err(Error::PayloadOversized {
+                    length: payload.len(),
+                    limit: MAX_PAYLOAD_LEN,
+                });
             }
         }
         Ok(())
@@ -175,13 +201,23 @@ mod payload_builder {
         assert_eq!(payload.to_vec(), [0x42, 0x00, 0x02, 0x04, 0x02]);
     }
 
+    #[test]
+    /// The builder should fail with an empty payload
+    fn build_empty() {
+        let e = PayloadBuilder::default().payload([]).build().unwrap_err();
+        assert!(matches!(e, Error::InvalidMessage(_)));
+    }
+
     #[test]
     /// The builder should fail with an oversized
     fn build_oversized() {
         let p = [0; (MAX_PAYLOAD_LEN + 1)];
         let e = PayloadBuilder::default().payload(p).build().unwrap_err();
         match e {
-            crate::error::Error::Undefined => (),
+            crate::error::Error::PayloadOversized { length, limit } => {
+                assert_eq!(length, MAX_PAYLOAD_LEN + 1);
+                assert_eq!(limit, MAX_PAYLOAD_LEN);
+            }
             _ => panic!(),
         }
     }
@@ -0,0 +1,196 @@
+//! Mobile Terminated Priority
+//!
+//! A mobile terminated priority is one of the information element types that
+//! compose a mobile terminated message. It is defined by an information
+//! element identifier (IEI) with value 0x46.
+
+use alloc::string::ToString;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use byteorder::BigEndian;
+use derive_builder::Builder;
+use log::debug;
+
+use crate::error::{Error, Result};
+use crate::io::{Read, ReadBytesExt, Write, WriteBytesExt};
+use crate::InformationElement;
+
+/// Highest priority level, i.e. the most urgent
+const MIN_PRIORITY_LEVEL: u16 = 1;
+/// Lowest priority level
+const MAX_PRIORITY_LEVEL: u16 = 5;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Builder, Clone, Debug, PartialEq, Eq, Hash)]
+#[builder(
+    pattern = "owned",
+    build_fn(error = "crate::error::Error", validate = "Self::validate")
+)]
+/// Mobile Terminated Priority
+///
+/// IEI: 0x46
+///
+/// Fixed total size of 5 bytes.
+///
+/// # Components
+///
+/// * Priority level: From 1 (highest) to 5 (lowest). MT messages in the
+///   SSD's queue are delivered by priority order.
+pub(super) struct Priority {
+    level: u16,
+}
+
+impl InformationElement for Priority {
+    /// Information Element Identifier
+    fn identifier(&self) -> u8 {
+        0x46
+    }
+
+    // Length field of the Priority element
+    //
+    // This is a fixed value, the priority level only.
+    fn len(&self) -> u16 {
+        2
+    }
+
+    fn fields(&self) -> Vec<(&'static str, usize)> {
+        vec![("Priority level", 2)]
+    }
+
+    fn write<W: Write>(&self, wtr: &mut W) -> Result<usize> {
+        wtr.write_u8(0x46)?;
+        wtr.write_u16::<BigEndian>(self.len())?;
+        wtr.write_u16::<BigEndian>(self.level)?;
+        Ok(5)
+    }
+}
+
+impl Priority {
+    /// Parse a Priority from a Read trait
+    pub(super) fn from_reader<R: Read>(mut rdr: R) -> Result<Priority> {
+        let iei = rdr.read_u8()?;
+        if iei != 0x46 {
+            debug!(
+                "Wrong IEI type for MT-Priority. Expected 0x46 instead of {}",
+                &iei
+            );
+            return Err(Error::WrongIEType("MT-Priority".to_string(), 0x46, iei));
+        }
+        let len = rdr.read_u16::<BigEndian>()?;
+        if len != 2 {
+            debug!("MT-Priority with unexpected length: {} bytes", len);
+            return Err(Error::InvalidIELength {
+                iei,
+                length: len,
+                offset: 1,
+            });
+        }
+        let level = rdr.read_u16::<BigEndian>()?;
+        if !(MIN_PRIORITY_LEVEL..=MAX_PRIORITY_LEVEL).contains(&level) {
+            return Err(Error::InvalidPriority(level));
+        }
+        Ok(Priority { level })
+    }
+
+    /// Priority level, from 1 (highest) to 5 (lowest)
+    pub(super) fn level(&self) -> u16 {
+        self.level
+    }
+
+    #[allow(dead_code)]
+    pub(crate) fn builder() -> PriorityBuilder {
+        PriorityBuilder::default()
+    }
+}
+
+impl core::fmt::Display for Priority {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "Level: {}", self.level)
+    }
+}
+
+#[cfg(test)]
+mod test_mt_priority {
+    use super::{Error, InformationElement, Priority};
+
+    #[test]
+    fn write() {
+        let priority = Priority { level: 3 };
+        assert_eq!(priority.to_vec(), [0x46, 0x00, 0x02, 0x00, 0x03]);
+    }
+
+    #[test]
+    fn read() {
+        let priority = Priority::from_reader([0x46, 0x00, 0x02, 0x00, 0x01].as_slice()).unwrap();
+        assert_eq!(priority.level(), 1);
+    }
+
+    #[test]
+    fn read_out_of_range() {
+        for level in [0x00, 0x06] {
+            let priority = Priority::from_reader([0x46, 0x00, 0x02, 0x00, level].as_slice());
+            assert!(matches!(priority, Err(Error::InvalidPriority(l)) if l == u16::from(level)));
+        }
+    }
+
+    #[test]
+    fn roundtrip_write_n_read() {
+        for level in 1..=5 {
+            let priority = Priority { level };
+            assert_eq!(
+                priority,
+                Priority::from_reader(priority.to_vec().as_slice()).unwrap()
+            );
+        }
+    }
+}
+
+#[cfg(all(test, feature = "serde"))]
+mod test_priority_serde {
+    use super::Priority;
+
+    #[test]
+    fn roundtrip() {
+        let priority = Priority { level: 2 };
+        let json = serde_json::to_string(&priority).unwrap();
+
+        let roundtrip: Priority = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(priority, roundtrip);
+    }
+}
+
+impl PriorityBuilder {
+    fn validate(&self) -> Result<()> {
+        if let Some(level) = self.level {
+            if !(MIN_PRIORITY_LEVEL..=MAX_PRIORITY_LEVEL).contains(&level) {
+                return Err(Error::InvalidPriority(level));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_priority_builder {
+    use super::{Error, PriorityBuilder};
+
+    #[test]
+    fn build_missing_required() {
+        let priority = PriorityBuilder::default().build();
+        assert!(matches!(priority, Err(Error::UninitializedFieldError(_))))
+    }
+
+    #[test]
+    fn build() {
+        let priority = PriorityBuilder::default().level(5).build().unwrap();
+        assert_eq!(priority.level(), 5);
+    }
+
+    #[test]
+    fn build_out_of_range() {
+        let priority = PriorityBuilder::default().level(6).build();
+        assert!(matches!(priority, Err(Error::InvalidPriority(6))))
+    }
+}
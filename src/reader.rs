@@ -0,0 +1,235 @@
+//! Sequential reader of Messages
+//!
+//! Multiple Direct-IP messages can be concatenated in the same file or
+//! stream. A [MessageReader] yields one message after another until the end
+//! of the input.
+
+use std::io::Read;
+
+use crate::error::{Error, Result};
+use crate::Message;
+
+/// Iterator over consecutive Messages from a reader
+///
+/// Each message is framed by its overall length, thus an invalid
+/// Information Element is reported as an error and the reader continues
+/// with the next message. If the protocol version is not valid, the length
+/// can't be trusted either, so the reader skips bytes until the next
+/// possible start of a message (a protocol version 1).
+///
+/// Errors are positioned relative to the start of the input.
+///
+/// # Example
+///
+/// ```
+/// use directip::MessageReader;
+///
+/// let mut buffer = directip::sample().to_vec();
+/// buffer.extend(directip::sample().to_vec());
+///
+/// let messages = MessageReader::new(buffer.as_slice())
+///     .collect::<Result<Vec<_>, _>>()
+///     .unwrap();
+/// assert_eq!(messages.len(), 2);
+/// ```
+pub struct MessageReader<R> {
+    rdr: R,
+    // Bytes consumed so far
+    position: usize,
+    // Skip bytes until the next possible start of a message
+    resync: bool,
+    done: bool,
+}
+
+impl<R: Read> MessageReader<R> {
+    pub fn new(rdr: R) -> MessageReader<R> {
+        MessageReader {
+            rdr,
+            position: 0,
+            resync: false,
+            done: false,
+        }
+    }
+
+    /// Position, in bytes from the start of the input, of the next Message
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Read a single byte, or None at the end of input
+    fn read_byte(&mut self) -> Result<Option<u8>> {
+        let mut buffer = [0u8; 1];
+        loop {
+            match self.rdr.read(&mut buffer) {
+                Ok(0) => return Ok(None),
+                Ok(_) => {
+                    self.position += 1;
+                    return Ok(Some(buffer[0]));
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Read up to the size of the buffer, returning the number of bytes read
+    fn read_up_to(&mut self, buffer: &mut [u8]) -> Result<usize> {
+        let mut n = 0;
+        while n < buffer.len() {
+            match self.rdr.read(&mut buffer[n..]) {
+                Ok(0) => break,
+                Ok(m) => n += m,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            }
+        }
+        self.position += n;
+        Ok(n)
+    }
+
+    fn read_message(&mut self) -> Option<Result<Message>> {
+        // Protocol version
+        let start = self.position;
+        let version = loop {
+            match self.read_byte() {
+                Ok(None) => return None,
+                Ok(Some(1)) => break 1,
+                Ok(Some(_)) if self.resync => continue,
+                Ok(Some(version)) => {
+                    self.resync = true;
+                    return Some(Err(Error::UnsupportedVersion {
+                        version,
+                        offset: start,
+                    }));
+                }
+                Err(e) => return Some(Err(e)),
+            }
+        };
+        self.resync = false;
+        let start = self.position - 1;
+
+        // Message total length
+        let mut length = [0u8; 2];
+        match self.read_up_to(&mut length) {
+            Ok(2) => (),
+            Ok(_) => return Some(Err(Error::IO(std::io::ErrorKind::UnexpectedEof.into()))),
+            Err(e) => return Some(Err(e)),
+        }
+        let declared = usize::from(u16::from_be_bytes(length));
+
+        let mut elements = vec![0u8; declared];
+        match self.read_up_to(&mut elements) {
+            Ok(n) if n == declared => (),
+            Ok(n) => {
+                return Some(Err(Error::MessageLengthMismatch {
+                    declared,
+                    actual: n,
+                }))
+            }
+            Err(e) => return Some(Err(e)),
+        }
+
+        let header = [version, length[0], length[1]];
+        let frame = header.chain(elements.as_slice());
+        Some(Message::from_reader(frame).map_err(|e| e.shift(start)))
+    }
+}
+
+impl<R: Read> Iterator for MessageReader<R> {
+    type Item = Result<Message>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let item = self.read_message();
+        // Can't recover from an incomplete message or a failing reader
+        if matches!(
+            item,
+            None | Some(Err(Error::IO(_))) | Some(Err(Error::MessageLengthMismatch { .. }))
+        ) {
+            self.done = true;
+        }
+        item
+    }
+}
+
+#[cfg(test)]
+mod test_message_reader {
+    use super::MessageReader;
+    use crate::error::Error;
+    use crate::sample;
+
+    #[test]
+    fn empty() {
+        let mut reader = MessageReader::new([].as_slice());
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn concatenated() {
+        let mut buffer = sample().to_vec();
+        buffer.extend(sample().to_vec());
+        buffer.extend(sample().to_vec());
+
+        let mut reader = MessageReader::new(buffer.as_slice());
+        for i in 1..=3 {
+            let msg = reader.next().unwrap().unwrap();
+            assert_eq!(msg.to_vec(), sample().to_vec());
+            assert_eq!(reader.position(), i * sample().to_vec().len());
+        }
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    // Invalid element is reported and the reader continues with the next
+    fn invalid_element() {
+        let size = sample().to_vec().len();
+        let mut buffer = sample().to_vec();
+        // An invalid length for the MT Confirmation
+        buffer[5] = 0x18;
+        buffer.extend(sample().to_vec());
+
+        let mut reader = MessageReader::new(buffer.as_slice());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(Error::InvalidIELength {
+                iei: 0x44,
+                length: 24,
+                offset: 4
+            }))
+        ));
+        assert_eq!(reader.position(), size);
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    // Garbage between messages is skipped after reporting its position
+    fn resync() {
+        let size = sample().to_vec().len();
+        let mut buffer = sample().to_vec();
+        buffer.extend([0xff, 0x00, 0xfe]);
+        buffer.extend(sample().to_vec());
+
+        let mut reader = MessageReader::new(buffer.as_slice());
+        assert!(reader.next().unwrap().is_ok());
+        assert!(matches!(
+            reader.next(),
+            Some(Err(Error::UnsupportedVersion { version: 0xff, offset })) if offset == size
+        ));
+        assert!(reader.next().unwrap().is_ok());
+        assert!(reader.next().is_none());
+    }
+
+    #[test]
+    fn truncated() {
+        let buffer = sample().to_vec();
+        let mut reader = MessageReader::new(&buffer[..buffer.len() - 1]);
+        assert!(matches!(
+            reader.next(),
+            Some(Err(Error::MessageLengthMismatch { .. }))
+        ));
+        assert!(reader.next().is_none());
+    }
+}
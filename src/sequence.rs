@@ -0,0 +1,257 @@
+//! Tracking of the MOMSN sequence of each modem
+//!
+//! The MO Message Sequence Number is incremented by the modem at each
+//! session, wrapping around from 65535 to 0. Thus, following the sequence
+//! of each IMEI reveals lost messages, or messages received more than once.
+
+use alloc::collections::BTreeMap;
+
+use crate::mo::MOMessage;
+use crate::Imei;
+
+/// Any MOMSN further ahead than this is considered behind the last one
+const HALF_RANGE: u16 = 0x8000;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// Position of a MOMSN relative to the last one of the same modem
+pub enum Sequence {
+    /// First MOMSN of a modem not seen before
+    First(u16),
+    /// The next MOMSN, as expected
+    InOrder(u16),
+    /// The next MOMSN, wrapping around from 65535 to 0
+    Wrapped,
+    /// MOMSNs were skipped, thus `missing` sessions were possibly lost
+    Gap {
+        expected: u16,
+        received: u16,
+        missing: u16,
+    },
+    /// Same MOMSN as the last one, such as a retransmission
+    Duplicate(u16),
+    /// MOMSN behind the last one, either late or repeated
+    OutOfOrder { last: u16, received: u16 },
+}
+
+impl Sequence {
+    /// Anything other than the expected continuation of the sequence
+    pub fn is_anomaly(&self) -> bool {
+        !matches!(
+            self,
+            Sequence::First(_) | Sequence::InOrder(_) | Sequence::Wrapped
+        )
+    }
+}
+
+impl core::fmt::Display for Sequence {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        match self {
+            Sequence::First(momsn) => write!(f, "First MOMSN {}", momsn),
+            Sequence::InOrder(momsn) => write!(f, "MOMSN {}", momsn),
+            Sequence::Wrapped => write!(f, "MOMSN wrapped around to 0"),
+            Sequence::Gap {
+                expected,
+                received,
+                missing,
+            } => write!(
+                f,
+                "Expected MOMSN {} but received {}, {} missing",
+                expected, received, missing
+            ),
+            Sequence::Duplicate(momsn) => write!(f, "Duplicate MOMSN {}", momsn),
+            Sequence::OutOfOrder { last, received } => {
+                write!(f, "MOMSN {} behind the last one, {}", received, last)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+/// Last MOMSN of each modem
+///
+/// Only moving forward updates the last MOMSN, so a late or repeated
+/// message doesn't rewind the sequence.
+pub struct MomsnTracker {
+    last: BTreeMap<Imei, u16>,
+}
+
+impl MomsnTracker {
+    pub fn new() -> Self {
+        MomsnTracker::default()
+    }
+
+    /// Track the MOMSN of a message, None if it has no MO Header
+    pub fn track(&mut self, msg: &MOMessage) -> Option<Sequence> {
+        let header = msg.header()?;
+        Some(self.track_momsn(header.imei(), header.momsn()))
+    }
+
+    /// Track a MOMSN received from a modem
+    pub fn track_momsn(&mut self, imei: Imei, momsn: u16) -> Sequence {
+        let last = match self.last.get(&imei) {
+            Some(last) => *last,
+            None => {
+                self.last.insert(imei, momsn);
+                return Sequence::First(momsn);
+            }
+        };
+        let sequence = match momsn.wrapping_sub(last) {
+            0 => return Sequence::Duplicate(momsn),
+            1 if momsn == 0 => Sequence::Wrapped,
+            1 => Sequence::InOrder(momsn),
+            n if n < HALF_RANGE => Sequence::Gap {
+                expected: last.wrapping_add(1),
+                received: momsn,
+                missing: n - 1,
+            },
+            _ => {
+                return Sequence::OutOfOrder {
+                    last,
+                    received: momsn,
+                }
+            }
+        };
+        self.last.insert(imei, momsn);
+        sequence
+    }
+
+    /// Last MOMSN received from a modem
+    pub fn last(&self, imei: &Imei) -> Option<u16> {
+        self.last.get(imei).copied()
+    }
+
+    /// Last MOMSN of every modem, such as to persist the state
+    pub fn iter(&self) -> impl Iterator<Item = (Imei, u16)> + '_ {
+        self.last.iter().map(|(imei, momsn)| (*imei, *momsn))
+    }
+}
+
+/// Restore a tracker from the last MOMSN of each modem
+impl FromIterator<(Imei, u16)> for MomsnTracker {
+    fn from_iter<I: IntoIterator<Item = (Imei, u16)>>(iter: I) -> Self {
+        MomsnTracker {
+            last: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_sequence {
+    use super::{MomsnTracker, Sequence};
+    use crate::Imei;
+
+    fn imei() -> Imei {
+        "300234010753376".parse().unwrap()
+    }
+
+    #[test]
+    fn in_order() {
+        let mut tracker = MomsnTracker::new();
+        assert_eq!(tracker.track_momsn(imei(), 7), Sequence::First(7));
+        assert_eq!(tracker.track_momsn(imei(), 8), Sequence::InOrder(8));
+        assert_eq!(tracker.last(&imei()), Some(8));
+    }
+
+    #[test]
+    fn wrapped() {
+        let mut tracker = MomsnTracker::new();
+        tracker.track_momsn(imei(), 65535);
+        assert_eq!(tracker.track_momsn(imei(), 0), Sequence::Wrapped);
+        assert!(!Sequence::Wrapped.is_anomaly());
+    }
+
+    #[test]
+    fn gap() {
+        let mut tracker = MomsnTracker::new();
+        tracker.track_momsn(imei(), 10);
+        let sequence = tracker.track_momsn(imei(), 14);
+        assert_eq!(
+            sequence,
+            Sequence::Gap {
+                expected: 11,
+                received: 14,
+                missing: 3
+            }
+        );
+        assert!(sequence.is_anomaly());
+        assert_eq!(tracker.last(&imei()), Some(14));
+    }
+
+    #[test]
+    fn gap_across_wraparound() {
+        let mut tracker = MomsnTracker::new();
+        tracker.track_momsn(imei(), 65534);
+        assert_eq!(
+            tracker.track_momsn(imei(), 1),
+            Sequence::Gap {
+                expected: 65535,
+                received: 1,
+                missing: 2
+            }
+        );
+    }
+
+    #[test]
+    fn duplicate() {
+        let mut tracker = MomsnTracker::new();
+        tracker.track_momsn(imei(), 10);
+        assert_eq!(tracker.track_momsn(imei(), 10), Sequence::Duplicate(10));
+    }
+
+    #[test]
+    // A late message doesn't rewind the sequence
+    fn out_of_order() {
+        let mut tracker = MomsnTracker::new();
+        tracker.track_momsn(imei(), 10);
+        tracker.track_momsn(imei(), 12);
+        assert_eq!(
+            tracker.track_momsn(imei(), 11),
+            Sequence::OutOfOrder {
+                last: 12,
+                received: 11
+            }
+        );
+        assert_eq!(tracker.last(&imei()), Some(12));
+        assert_eq!(tracker.track_momsn(imei(), 13), Sequence::InOrder(13));
+    }
+
+    #[test]
+    fn independent_modems() {
+        let other: Imei = "012345678901237".parse().unwrap();
+        let mut tracker = MomsnTracker::new();
+        tracker.track_momsn(imei(), 10);
+        assert_eq!(tracker.track_momsn(other, 500), Sequence::First(500));
+        assert_eq!(tracker.track_momsn(imei(), 11), Sequence::InOrder(11));
+    }
+
+    #[test]
+    fn restore() {
+        let mut tracker = MomsnTracker::new();
+        tracker.track_momsn(imei(), 10);
+        let mut restored: MomsnTracker = tracker.iter().collect();
+        assert_eq!(restored, tracker);
+        assert_eq!(restored.track_momsn(imei(), 11), Sequence::InOrder(11));
+    }
+
+    #[test]
+    fn track_message() {
+        let msg = crate::mo::MOMessage::builder()
+            .cdr_uid(9999)
+            .imei(imei())
+            .session_status(crate::mo::SessionStatus::Success)
+            .momsn(999)
+            .mtmsn(0)
+            .time_of_session("2000-03-14T12:12:12Z".parse().unwrap())
+            .build()
+            .unwrap();
+        let mut tracker = MomsnTracker::new();
+        assert_eq!(tracker.track(&msg), Some(Sequence::First(999)));
+        assert_eq!(tracker.track(&msg), Some(Sequence::Duplicate(999)));
+
+        let confirmation = crate::mo::MOMessage::builder()
+            .confirmation(true)
+            .build()
+            .unwrap();
+        assert_eq!(tracker.track(&confirmation), None);
+    }
+}
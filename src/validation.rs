@@ -0,0 +1,261 @@
+//! Verification of a Message against the Direct-IP specification
+//!
+//! Parsing is lenient, so that messages from a misbehaving gateway, or test
+//! fixtures, can still be inspected. Here, a message is verified for what
+//! the parser tolerates, reporting every violation found instead of
+//! stopping at the first one.
+
+use alloc::vec;
+use alloc::vec::Vec;
+
+use core::fmt;
+
+use crate::borrowed::MessageRef;
+use crate::mo::Coordinate;
+use crate::{direction, Direction};
+
+/// Assign MTMSN disposition flag in the MT Header
+const ASSIGN_MTMSN: u16 = 0x0020;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+/// A deviation from the Direct-IP specification
+pub enum Violation {
+    /// More than one Information Element with the same identifier
+    DuplicateElement { iei: u8 },
+    /// Neither a Header nor a Confirmation, one of which is mandatory
+    MissingHeader,
+    /// Information Element not allowed in this message, such as one of the
+    /// opposite direction, or anything together with a Confirmation
+    ForbiddenElement { iei: u8 },
+    /// Length field not valid for this type of Information Element
+    InvalidLength { iei: u8, length: u16 },
+    /// MT Header assigning an MTMSN outside of the range 1-65535
+    MtmsnOutOfRange(u32),
+    /// Location Information with a coordinate out of the valid range, see
+    /// [crate::mo::Coordinate::decode]
+    InvalidCoordinate { iei: u8 },
+    /// MO Header with a time of session that doesn't fit in its 4 bytes,
+    /// in seconds since 1970
+    InvalidTimeOfSession(i64),
+    /// Encoded Message that can't be framed by its lengths
+    InvalidFraming,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::DuplicateElement { iei } => write!(f, "Duplicate IEI 0x{:02x}", iei),
+            Violation::MissingHeader => write!(f, "Missing Header"),
+            Violation::ForbiddenElement { iei } => write!(f, "Forbidden IEI 0x{:02x}", iei),
+            Violation::InvalidLength { iei, length } => {
+                write!(f, "Invalid length {} for IEI 0x{:02x}", length, iei)
+            }
+            Violation::MtmsnOutOfRange(mtmsn) => write!(f, "MTMSN out of range: {}", mtmsn),
+            Violation::InvalidCoordinate { iei } => {
+                write!(f, "Invalid coordinate for IEI 0x{:02x}", iei)
+            }
+            Violation::InvalidTimeOfSession(t) => write!(f, "Invalid time of session: {}", t),
+            Violation::InvalidFraming => write!(f, "Invalid framing"),
+        }
+    }
+}
+
+/// Valid length for a known Information Element, None if unknown
+fn valid_length(iei: u8, length: u16) -> Option<bool> {
+    let length = usize::from(length);
+    match iei {
+        // MO Header, Payload, Location, and Confirmation
+        0x01 => Some(length == 28),
+        0x02 => Some((1..=crate::mo::MAX_PAYLOAD_LEN).contains(&length)),
+        0x03 => Some(length == 11),
+        0x05 => Some(length == 1),
+        // MT Header, Payload, Location, Confirmation, and Priority
+        0x41 => Some(length == 21),
+        0x42 => Some((1..=crate::mt::MAX_PAYLOAD_LEN).contains(&length)),
+        0x43 => Some(length == 11),
+        0x44 => Some(length == 25),
+        0x46 => Some(length == 2),
+        _ => None,
+    }
+}
+
+/// Verify a Message, returning all violations found
+pub(crate) fn validate(msg: &MessageRef) -> Vec<Violation> {
+    let mut violations = vec![];
+    let msg_direction = msg.direction();
+    let (header, confirmation) = match msg_direction {
+        Direction::MO => (0x01, 0x05),
+        Direction::MT => (0x41, 0x44),
+    };
+
+    let mut seen: Vec<u8> = vec![];
+    for element in msg.elements() {
+        let iei = element.identifier();
+        if seen.contains(&iei) {
+            violations.push(Violation::DuplicateElement { iei });
+        } else {
+            seen.push(iei);
+        }
+        if direction(iei) != Some(msg_direction) {
+            violations.push(Violation::ForbiddenElement { iei });
+        }
+        if valid_length(iei, element.len()) == Some(false) {
+            violations.push(Violation::InvalidLength {
+                iei,
+                length: element.len(),
+            });
+            continue;
+        }
+        if iei == 0x41 {
+            let body = element.body();
+            let client_msg_id = u32::from_be_bytes([body[0], body[1], body[2], body[3]]);
+            let flags = u16::from_be_bytes([body[19], body[20]]);
+            if (flags & ASSIGN_MTMSN != 0) && !(1..=0xffff).contains(&client_msg_id) {
+                violations.push(Violation::MtmsnOutOfRange(client_msg_id));
+            }
+        }
+        if iei == 0x03 || iei == 0x43 {
+            let coordinate: &[u8; 7] = element.body()[..7]
+                .try_into()
+                .expect("Location has a valid length");
+            if Coordinate::decode(coordinate).is_err() {
+                violations.push(Violation::InvalidCoordinate { iei });
+            }
+        }
+    }
+
+    if seen.contains(&confirmation) {
+        // A Confirmation is sent alone
+        for iei in seen.iter().filter(|iei| **iei != confirmation) {
+            violations.push(Violation::ForbiddenElement { iei: *iei });
+        }
+    } else if !seen.contains(&header) {
+        violations.push(Violation::MissingHeader);
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod test_validation {
+    use super::Violation;
+    use crate::MessageRef;
+
+    // MO Header and Payload ("Hello World!")
+    const MO: [u8; 49] = [
+        0x01, 0x00, 0x2e, 0x01, 0x00, 0x1c, 0x00, 0x00, 0x27, 0x0f, 0x00, 0x01, 0x02, 0x03, 0x04,
+        0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x00, 0x03, 0xe7, 0x00, 0x6f,
+        0x38, 0xce, 0x2c, 0x9c, 0x02, 0x00, 0x0c, 0x48, 0x65, 0x6c, 0x6c, 0x6f, 0x20, 0x57, 0x6f,
+        0x72, 0x6c, 0x64, 0x21,
+    ];
+
+    fn validate(buffer: &[u8]) -> Vec<Violation> {
+        MessageRef::from_bytes(buffer).unwrap().validate()
+    }
+
+    /// Overwrite the overall message length after editing the elements
+    fn frame(mut buffer: Vec<u8>) -> Vec<u8> {
+        let length = u16::try_from(buffer.len() - 3).unwrap().to_be_bytes();
+        buffer[1..3].copy_from_slice(&length);
+        buffer
+    }
+
+    #[test]
+    fn valid() {
+        assert!(validate(&MO).is_empty());
+        assert!(validate(&crate::sample().to_vec()).is_empty());
+    }
+
+    #[test]
+    fn duplicate_payload() {
+        let mut buffer = MO.to_vec();
+        buffer.extend_from_slice(&MO[34..]);
+        assert_eq!(
+            validate(&frame(buffer)),
+            [Violation::DuplicateElement { iei: 0x02 }]
+        );
+    }
+
+    #[test]
+    fn missing_header() {
+        let mut buffer = MO[..3].to_vec();
+        buffer.extend_from_slice(&MO[34..]);
+        assert_eq!(validate(&frame(buffer)), [Violation::MissingHeader]);
+    }
+
+    #[test]
+    fn forbidden_direction() {
+        // An MT Priority in an MO message
+        let mut buffer = MO.to_vec();
+        buffer.extend_from_slice(&[0x46, 0x00, 0x02, 0x00, 0x01]);
+        assert_eq!(
+            validate(&frame(buffer)),
+            [Violation::ForbiddenElement { iei: 0x46 }]
+        );
+    }
+
+    #[test]
+    fn forbidden_with_confirmation() {
+        let mut buffer = MO.to_vec();
+        buffer.extend_from_slice(&[0x05, 0x00, 0x01, 0x01]);
+        assert_eq!(
+            validate(&frame(buffer)),
+            [
+                Violation::ForbiddenElement { iei: 0x01 },
+                Violation::ForbiddenElement { iei: 0x02 }
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_length() {
+        // An MO Location with a single byte
+        let mut buffer = MO.to_vec();
+        buffer.extend_from_slice(&[0x03, 0x00, 0x01, 0x00]);
+        assert_eq!(
+            validate(&frame(buffer)),
+            [Violation::InvalidLength {
+                iei: 0x03,
+                length: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn invalid_coordinate() {
+        // An MO Location with 91 degrees of latitude
+        let mut buffer = MO.to_vec();
+        buffer.extend_from_slice(&[0x03, 0x00, 0x0b, 0x00, 0x5b, 0x00, 0x00, 0x00, 0x00, 0x00]);
+        buffer.extend_from_slice(&[0x00, 0x00, 0x00, 0x0a]);
+        assert_eq!(
+            validate(&frame(buffer)),
+            [Violation::InvalidCoordinate { iei: 0x03 }]
+        );
+    }
+
+    #[test]
+    fn mtmsn_out_of_range() {
+        // MT Header with Assign MTMSN and client message ID 0x10000
+        let mut buffer = vec![0x01, 0x00, 0x00, 0x41, 0x00, 0x15, 0x00, 0x01, 0x00, 0x00];
+        buffer.extend_from_slice(b"300234010753376");
+        buffer.extend_from_slice(&[0x00, 0x20, 0x42, 0x00, 0x01, 0x42]);
+        assert_eq!(
+            validate(&frame(buffer)),
+            [Violation::MtmsnOutOfRange(0x10000)]
+        );
+    }
+
+    #[test]
+    fn multiple() {
+        let mut buffer = MO[..3].to_vec();
+        buffer.extend_from_slice(&MO[34..]);
+        buffer.extend_from_slice(&MO[34..]);
+        assert_eq!(
+            validate(&frame(buffer)),
+            [
+                Violation::DuplicateElement { iei: 0x02 },
+                Violation::MissingHeader
+            ]
+        );
+    }
+}